@@ -0,0 +1,50 @@
+// Server that reads its settings from a config file named on the command line.
+// Flags given on the command line take precedence over the config file.
+//
+// USAGE:
+//     cargo run --example config --features config -- --config server.toml --max_qps 50
+
+use gflags::custom::{Error, Result};
+use std::path::Path;
+use std::process;
+
+gflags::define! {
+    /// Path of a TOML or JSON config file.
+    --config: &Path
+
+    --max_qps: u32 = 100
+
+    --min_workers: u32 = 1
+    --max_workers: u32 = 8
+
+    --tls = false
+    --tls_cert: &Path
+}
+
+gflags::constraint! {
+    TLS requires TLS_CERT;
+}
+
+gflags::on_parsed!(check_workers);
+
+fn check_workers() -> Result<()> {
+    if MIN_WORKERS.flag > MAX_WORKERS.flag {
+        return Err(Error::new("--min_workers must not exceed --max_workers"));
+    }
+    Ok(())
+}
+
+fn main() {
+    gflags::parse();
+
+    if CONFIG.is_present() {
+        if let Err(err) = gflags::config::load(CONFIG.flag) {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    }
+
+    println!("max_qps = {} from {}", MAX_QPS.flag, MAX_QPS.origin());
+    println!("workers = {}..{}", MIN_WORKERS.flag, MAX_WORKERS.flag);
+    println!("tls = {}", TLS.flag);
+}
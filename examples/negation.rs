@@ -0,0 +1,35 @@
+// Program with flags named like the negation of a bool flag. A spelling that
+// is the name of a flag always sets that flag; the other spellings negate.
+//
+// USAGE:
+//     cargo run --example negation -- --no-cache
+//     cargo run --example negation -- --nocache --notify=sms
+
+gflags::define! {
+    /// Reuse results from previous runs.
+    --cache = true
+
+    /// Delete the cache before running.
+    --no-cache = false
+
+    /// Send notifications.
+    --tify = true
+
+    /// Channel used for notifications.
+    --notify: &str = "email"
+
+    -h, --help = false
+}
+
+fn main() {
+    gflags::parse();
+
+    if HELP.flag {
+        gflags::print_help_and_exit(0);
+    }
+
+    println!("cache = {}", CACHE.flag);
+    println!("no_cache = {}", NO_CACHE.flag);
+    println!("tify = {}", TIFY.flag);
+    println!("notify = {}", NOTIFY.flag);
+}
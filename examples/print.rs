@@ -1,5 +1,6 @@
 use gflags::custom::{Arg, Error, Result, Value};
 use std::path::Path;
+use std::time::Duration;
 
 const ABOUT: &str = "
 Gflags is a library for distributed registration of command line flags in a
@@ -24,12 +25,23 @@ gflags::define! {
 
 gflags::define! {
     /// Comma-separated list of languages to offer in the 'lang' menu.
-    -l, --language <LANG> = "english,french,german"
+    -l, --language, --lang <LANG> = "english,french,german"
+}
+
+gflags::define! {
+    #[deprecated(note = "use --language instead", forward_to = "language")]
+    --locale <LANG>: &str
 }
 
 gflags::define! {
     /// Search for patterns from the given file, with one pattern per line.
     -f, --file: &Path
+
+    /// Character encoding of the patterns file given by --file.
+    --file_encoding <ENCODING> = "utf-8"
+
+    /// Give up on a search that runs longer than this.
+    --timeout <DURATION>: Duration = Duration::from_secs(30)
 }
 
 gflags::define! {
@@ -37,7 +49,29 @@ gflags::define! {
 }
 
 gflags::define! {
+    #[no_negation]
     -h, --help = false
+
+    /// Print help including hidden flags.
+    --helpfull = false
+
+    /// Print the parsed arguments in debug format.
+    #[hidden]
+    --debug_args = false
+}
+
+gflags::constraint! {
+    HELP conflicts_with HELPFULL;
+    FILE_ENCODING requires FILE;
+}
+
+gflags::on_parsed!(check_language);
+
+fn check_language() -> Result<()> {
+    if LANGUAGE.flag.is_empty() {
+        return Err(Error::new("--language must not be empty"));
+    }
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -59,19 +93,27 @@ impl Value for Color {
 }
 
 fn main() {
-    let args = gflags::parse();
+    let args = gflags::ParseOptions::new()
+        .allow_unknown(&["legacy_mode"])
+        .parse();
 
-    if HELP.flag {
+    if HELP.flag || HELPFULL.flag {
         print_help_and_exit();
     }
 
     println!("big_menu = {}", BIG_MENU.flag);
     println!("language = {}", LANGUAGE.flag);
+    println!("language origin = {}", LANGUAGE.origin());
     if FILE.is_present() {
         println!("file = {}", FILE.flag.display());
+        println!("file_encoding = {}", FILE_ENCODING.flag);
     }
     println!("color = {:?}", COLOR.flag);
+    println!("timeout = {:?}", TIMEOUT.flag);
     println!("args = {:?}", args);
+    if DEBUG_ARGS.flag {
+        println!("{:#?}", args);
+    }
 }
 
 fn print_help_and_exit() -> ! {
@@ -82,5 +124,9 @@ fn print_help_and_exit() -> ! {
         authors = env!("CARGO_PKG_AUTHORS"),
         about = ABOUT,
     );
-    gflags::print_help_and_exit(0);
+    if HELPFULL.flag {
+        gflags::print_full_help_and_exit(0);
+    } else {
+        gflags::print_help_and_exit(0);
+    }
 }
@@ -0,0 +1,35 @@
+// Program using stricter POSIX-style command line conventions: flags must come
+// before the positional arguments, short flags may not be clustered, and bool
+// flags have no --noname form. Long flags may be abbreviated, as in --verb.
+//
+// USAGE:
+//     cargo run --example strict -- -v -o out.txt input.txt
+//     cargo run --example strict -- --verb --out=out.txt input.txt
+//     cargo run --example strict -- -v3 -o=out.txt input.txt
+
+gflags::define! {
+    -v, --verbose = false
+    -q, --quiet = false
+    -o, --output: &str = "-"
+    --version = false
+    -h, --help = false
+}
+
+fn main() {
+    let options = gflags::ParseOptions::new()
+        .stop_at_first_positional(true)
+        .cluster_shorts(false)
+        .short_equals(true)
+        .negation(false)
+        .abbreviations(true);
+    let args = options.parse();
+
+    if HELP.flag {
+        options.print_help_and_exit(0);
+    }
+
+    println!("verbose = {}", VERBOSE.flag);
+    println!("verbosity = {}", VERBOSE.repeat_count());
+    println!("output = {}", OUTPUT.flag);
+    println!("args = {:?}", args);
+}
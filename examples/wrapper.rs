@@ -0,0 +1,22 @@
+// Wrapper around another command that handles some flags itself and forwards
+// the rest.
+//
+// USAGE:
+//     cargo run --example wrapper -- --dry_run -v --color=always build
+
+gflags::define! {
+    /// Print the forwarded command instead of running it.
+    --dry_run = false
+
+    -v, --verbose = false
+}
+
+fn main() {
+    let args = gflags::ParseOptions::new()
+        .pass_through_unknown(true)
+        .parse();
+
+    println!("dry_run = {}", DRY_RUN.flag);
+    println!("verbose = {}", VERBOSE.flag);
+    println!("forward = {:?}", args);
+}
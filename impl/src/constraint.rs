@@ -0,0 +1,90 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::punctuated::Punctuated;
+use syn::{Path, Token};
+
+pub struct Constraints {
+    pub constraints: Vec<Constraint>,
+}
+
+pub struct Constraint {
+    pub flag: Path,
+    pub kind: Kind,
+    pub others: Punctuated<Path, Token![,]>,
+}
+
+pub enum Kind {
+    Requires,
+    ConflictsWith,
+}
+
+mod keyword {
+    syn::custom_keyword!(requires);
+    syn::custom_keyword!(conflicts_with);
+}
+
+impl Parse for Constraints {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut constraints = Vec::new();
+        loop {
+            constraints.push(input.parse()?);
+            while input.parse::<Option<Token![;]>>()?.is_some() {}
+            if input.is_empty() {
+                break;
+            }
+        }
+        Ok(Constraints { constraints })
+    }
+}
+
+impl Parse for Constraint {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let flag = input.call(Path::parse_mod_style)?;
+
+        let lookahead = input.lookahead1();
+        let kind = if lookahead.peek(keyword::requires) {
+            input.parse::<keyword::requires>()?;
+            Kind::Requires
+        } else if lookahead.peek(keyword::conflicts_with) {
+            input.parse::<keyword::conflicts_with>()?;
+            Kind::ConflictsWith
+        } else {
+            return Err(lookahead.error());
+        };
+
+        let mut others = Punctuated::new();
+        loop {
+            others.push_value(input.call(Path::parse_mod_style)?);
+            if !input.peek(Token![,]) {
+                break;
+            }
+            others.push_punct(input.parse()?);
+            if input.is_empty() || input.peek(Token![;]) {
+                break;
+            }
+        }
+
+        Ok(Constraint { flag, kind, others })
+    }
+}
+
+pub fn expand(input: Constraint) -> TokenStream {
+    let Constraint { flag, kind, others } = input;
+
+    let kind = match kind {
+        Kind::Requires => quote!(Requires),
+        Kind::ConflictsWith => quote!(ConflictsWith),
+    };
+    let others = others.iter();
+
+    quote! {
+        gflags::inventory::submit! {
+            gflags::registry::Constraint {
+                kind: gflags::registry::ConstraintKind::#kind,
+                flag: &#flag,
+                others: &[#(&#others),*],
+            }
+        }
+    }
+}
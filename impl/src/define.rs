@@ -1,4 +1,5 @@
-use crate::parse::Flag;
+use crate::name::{Long, Short};
+use crate::parse::{Deprecated, Flag};
 use crate::placeholder::PlaceholderToken;
 use crate::{error, infer};
 use proc_macro2::TokenStream;
@@ -7,9 +8,14 @@ use quote::{quote, ToTokens};
 pub fn expand(input: Flag) -> TokenStream {
     let Flag {
         doc,
+        deprecated,
+        hidden,
+        no_negation,
         vis,
         short,
+        short_aliases,
         long,
+        aliases,
         placeholder,
         ty,
         default,
@@ -23,8 +29,25 @@ pub fn expand(input: Flag) -> TokenStream {
         None => quote!(None),
     };
 
+    let deprecated = match deprecated {
+        Some(Deprecated { note, forward_to }) => {
+            let note = option(note);
+            let forward_to = option(forward_to);
+            quote! {
+                Some(gflags::registry::Deprecated {
+                    note: #note,
+                    forward_to: #forward_to,
+                })
+            }
+        }
+        None => quote!(None),
+    };
+
+    let negatable = !no_negation;
+    let short_aliases = short_aliases.iter().map(Short::ch);
     let ident = long.to_ident();
     let name_str = long.to_string();
+    let aliases = aliases.iter().map(Long::to_string);
     let ty = match ty {
         Some(ty) => ty,
         None => match &default {
@@ -55,6 +78,7 @@ pub fn expand(input: Flag) -> TokenStream {
 
     let init = match default {
         Some(default) => quote!(gflags::Flag::new(&(#default))),
+        None if infer::is_option(&ty) => quote!(gflags::Flag::new(&None)),
         None => quote!(gflags::Flag::null()),
     };
 
@@ -64,10 +88,24 @@ pub fn expand(input: Flag) -> TokenStream {
             gflags::registry::Flag {
                 doc: &[#(#doc),*],
                 short: #short,
+                short_aliases: &[#(#short_aliases),*],
                 name: #name_str,
+                aliases: &[#(#aliases),*],
                 placeholder: #placeholder,
+                deprecated: #deprecated,
+                hidden: #hidden,
+                negatable: #negatable,
+                file: file!(),
+                line: line!(),
                 parser: &#ident,
             }
         }
     }
 }
+
+fn option(string: Option<String>) -> TokenStream {
+    match string {
+        Some(string) => quote!(Some(#string)),
+        None => quote!(None),
+    }
+}
@@ -25,3 +25,13 @@ pub fn infer_type(expr: &TokenStream) -> Option<Type> {
         _ => None,
     }
 }
+
+pub fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(ty) if ty.qself.is_none() => match ty.path.segments.last() {
+            Some(segment) => segment.ident == "Option",
+            None => false,
+        },
+        _ => false,
+    }
+}
@@ -6,6 +6,7 @@
 
 extern crate proc_macro;
 
+mod constraint;
 mod define;
 mod error;
 mod infer;
@@ -18,7 +19,22 @@ use syn::parse_macro_input;
 
 #[proc_macro]
 pub fn define_impl(input: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(input as parse::Flag);
-    let expanded = define::expand(input);
+    let input = parse_macro_input!(input as parse::Flags);
+    let expanded = input
+        .flags
+        .into_iter()
+        .map(define::expand)
+        .collect::<proc_macro2::TokenStream>();
+    TokenStream::from(expanded)
+}
+
+#[proc_macro]
+pub fn constraint_impl(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as constraint::Constraints);
+    let expanded = input
+        .constraints
+        .into_iter()
+        .map(constraint::expand)
+        .collect::<proc_macro2::TokenStream>();
     TokenStream::from(expanded)
 }
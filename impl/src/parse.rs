@@ -1,40 +1,63 @@
 use crate::name::{Long, Name, Short};
 use crate::placeholder::Placeholder;
-use proc_macro2::TokenStream;
-use quote::quote;
+use proc_macro2::{Spacing, TokenStream};
+use syn::buffer::Cursor;
 use syn::parse::{Error, Parse, ParseStream, Result};
-use syn::{bracketed, LitStr, Token, Type, Visibility};
+use syn::{bracketed, parenthesized, Ident, LitStr, Token, Type, Visibility};
+
+pub struct Flags {
+    pub flags: Vec<Flag>,
+}
 
 pub struct Flag {
     pub doc: Vec<String>,
+    pub deprecated: Option<Deprecated>,
+    pub hidden: bool,
+    pub no_negation: bool,
     pub vis: Visibility,
     pub short: Option<Short>,
+    pub short_aliases: Vec<Short>,
     pub long: Long,
+    pub aliases: Vec<Long>,
     pub placeholder: Option<Placeholder>,
     pub ty: Option<Type>,
     pub default: Option<TokenStream>,
 }
 
+pub struct Deprecated {
+    pub note: Option<String>,
+    pub forward_to: Option<String>,
+}
+
 mod keyword {
+    syn::custom_keyword!(deprecated);
     syn::custom_keyword!(doc);
+    syn::custom_keyword!(hidden);
+    syn::custom_keyword!(no_negation);
 }
 
-impl Parse for Flag {
+impl Parse for Flags {
     fn parse(input: ParseStream) -> Result<Self> {
-        let mut doc = Vec::new();
-
-        while input.parse::<Option<Token![#]>>()?.is_some() {
-            let content;
-            bracketed!(content in input);
-            content.parse::<keyword::doc>()?;
-            content.parse::<Token![=]>()?;
-            let lit: LitStr = content.parse()?;
-            let mut string = lit.value();
-            if string.starts_with(' ') {
-                string.remove(0);
+        let mut flags = Vec::new();
+        loop {
+            flags.push(input.parse()?);
+            while input.parse::<Option<Token![;]>>()?.is_some() {}
+            if input.is_empty() {
+                break;
             }
-            doc.push(string);
         }
+        Ok(Flags { flags })
+    }
+}
+
+impl Parse for Flag {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let Attrs {
+            doc,
+            deprecated,
+            hidden,
+            no_negation,
+        } = input.call(parse_attrs)?;
 
         let vis: Visibility = input.parse()?;
 
@@ -43,38 +66,34 @@ impl Parse for Flag {
         }
 
         let mut short = None::<Short>;
+        let mut short_aliases = Vec::<Short>::new();
         let mut long = None::<Long>;
+        let mut aliases = Vec::<Long>::new();
         while input.peek(Token![-]) {
             match input.parse()? {
-                Name::Short(Short {
-                    hyphen,
-                    ch: short_ident,
-                }) => {
-                    if short.is_some() {
-                        let spanned = quote!(#hyphen #short_ident);
-                        let msg = "unsupported second short flag";
-                        return Err(Error::new_spanned(spanned, msg));
+                Name::Short(name) => {
+                    let ch = name.ch();
+                    let previous = short.iter().chain(&short_aliases);
+                    if previous.map(Short::ch).any(|prev| prev == ch) {
+                        return Err(Error::new_spanned(name, "duplicate short flag"));
+                    }
+                    if short.is_none() {
+                        short = Some(name);
+                    } else {
+                        short_aliases.push(name);
                     }
-                    short = Some(Short {
-                        hyphen,
-                        ch: short_ident,
-                    });
                 }
-                Name::Long(Long {
-                    hyphen1,
-                    hyphen2,
-                    segments: long_ident,
-                }) => {
-                    if long.is_some() {
-                        let spanned = quote!(#hyphen1 #hyphen2 #long_ident);
-                        let msg = "unsupported second long flag";
-                        return Err(Error::new_spanned(spanned, msg));
+                Name::Long(name) => {
+                    let string = name.to_string();
+                    let previous = long.iter().chain(&aliases);
+                    if previous.map(Long::to_string).any(|prev| prev == string) {
+                        return Err(Error::new_spanned(name, "duplicate long flag"));
+                    }
+                    if long.is_none() {
+                        long = Some(name);
+                    } else {
+                        aliases.push(name);
                     }
-                    long = Some(Long {
-                        hyphen1,
-                        hyphen2,
-                        segments: long_ident,
-                    });
                 }
             }
             if input.parse::<Option<Token![,]>>()?.is_none() {
@@ -105,7 +124,10 @@ impl Parse for Flag {
 
         let default = if input.peek(Token![=]) {
             input.parse::<Token![=]>()?;
-            let default: TokenStream = input.parse()?;
+            let default = parse_default(input)?;
+            if default.is_empty() {
+                return Err(input.error("expected default value"));
+            }
             Some(default)
         } else {
             None
@@ -113,12 +135,166 @@ impl Parse for Flag {
 
         Ok(Flag {
             doc,
+            deprecated,
+            hidden,
+            no_negation,
             vis,
             short,
+            short_aliases,
             long,
+            aliases,
             placeholder,
             ty,
             default,
         })
     }
 }
+
+struct Attrs {
+    doc: Vec<String>,
+    deprecated: Option<Deprecated>,
+    hidden: bool,
+    no_negation: bool,
+}
+
+fn parse_attrs(input: ParseStream) -> Result<Attrs> {
+    let mut doc = Vec::new();
+    let mut deprecated = None;
+    let mut hidden = false;
+    let mut no_negation = false;
+
+    while input.parse::<Option<Token![#]>>()?.is_some() {
+        let content;
+        bracketed!(content in input);
+        let lookahead = content.lookahead1();
+        if lookahead.peek(keyword::doc) {
+            content.parse::<keyword::doc>()?;
+            content.parse::<Token![=]>()?;
+            let lit: LitStr = content.parse()?;
+            let mut string = lit.value();
+            if string.starts_with(' ') {
+                string.remove(0);
+            }
+            doc.push(string);
+        } else if lookahead.peek(keyword::deprecated) {
+            let keyword: keyword::deprecated = content.parse()?;
+            if deprecated.is_some() {
+                let msg = "duplicate deprecated attribute";
+                return Err(Error::new_spanned(keyword, msg));
+            }
+            deprecated = Some(content.call(parse_deprecated)?);
+        } else if lookahead.peek(keyword::hidden) {
+            let keyword: keyword::hidden = content.parse()?;
+            if hidden {
+                let msg = "duplicate hidden attribute";
+                return Err(Error::new_spanned(keyword, msg));
+            }
+            hidden = true;
+        } else if lookahead.peek(keyword::no_negation) {
+            let keyword: keyword::no_negation = content.parse()?;
+            if no_negation {
+                let msg = "duplicate no_negation attribute";
+                return Err(Error::new_spanned(keyword, msg));
+            }
+            no_negation = true;
+        } else {
+            return Err(lookahead.error());
+        }
+    }
+
+    Ok(Attrs {
+        doc,
+        deprecated,
+        hidden,
+        no_negation,
+    })
+}
+
+// Accepts `#[deprecated]`, `#[deprecated = "note"]` and
+// `#[deprecated(since = "...", note = "...", forward_to = "name")]`.
+fn parse_deprecated(input: ParseStream) -> Result<Deprecated> {
+    let mut deprecated = Deprecated {
+        note: None,
+        forward_to: None,
+    };
+
+    if input.parse::<Option<Token![=]>>()?.is_some() {
+        let note: LitStr = input.parse()?;
+        deprecated.note = Some(note.value());
+    } else if !input.is_empty() {
+        let content;
+        parenthesized!(content in input);
+        while !content.is_empty() {
+            let key: Ident = content.parse()?;
+            content.parse::<Token![=]>()?;
+            let value: LitStr = content.parse()?;
+            if key == "note" {
+                deprecated.note = Some(value.value());
+            } else if key == "forward_to" {
+                let name = value.value();
+                if name.starts_with('-') {
+                    let msg = "expected flag name without leading dashes";
+                    return Err(Error::new_spanned(value, msg));
+                }
+                deprecated.forward_to = Some(name);
+            } else if key != "since" {
+                let msg = "expected `since`, `note` or `forward_to`";
+                return Err(Error::new_spanned(key, msg));
+            }
+            if content.parse::<Option<Token![,]>>()?.is_none() {
+                break;
+            }
+        }
+        if !content.is_empty() {
+            return Err(content.error("expected `,`"));
+        }
+    }
+
+    Ok(deprecated)
+}
+
+// The default value extends up to the start of the next flag definition in the
+// same macro invocation, which begins with `;`, a doc comment or attribute,
+// `pub`, `--long` or `-s,`.
+fn parse_default(input: ParseStream) -> Result<TokenStream> {
+    input.step(|cursor| {
+        let mut tokens = TokenStream::new();
+        let mut rest = *cursor;
+        while let Some((tt, next)) = rest.token_tree() {
+            if starts_next_flag(rest) {
+                break;
+            }
+            tokens.extend(Some(tt));
+            rest = next;
+        }
+        Ok((tokens, rest))
+    })
+}
+
+fn starts_next_flag(cursor: Cursor) -> bool {
+    if let Some((ident, _next)) = cursor.ident() {
+        return ident == "pub";
+    }
+    let (punct, next) = match cursor.punct() {
+        Some(punct) => punct,
+        None => return false,
+    };
+    match punct.as_char() {
+        ';' | '#' => true,
+        '-' => {
+            if punct.spacing() == Spacing::Joint {
+                if let Some((punct, _next)) = next.punct() {
+                    return punct.as_char() == '-';
+                }
+            }
+            match next.ident() {
+                Some((_ident, next)) => match next.punct() {
+                    Some((punct, _next)) => punct.as_char() == ',',
+                    None => false,
+                },
+                None => false,
+            }
+        }
+        _ => false,
+    }
+}
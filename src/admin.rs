@@ -0,0 +1,313 @@
+//! Local admin socket for inspecting and modifying flags in a running process.
+//!
+//! *This module is available only with the `admin` Cargo feature, on Unix.*
+//!
+//! [`serve`] listens on a Unix domain socket and answers a line-oriented
+//! protocol, one request per line:
+//!
+//! - `get NAME` &mdash; responds `ok VALUE` with the current value of the flag.
+//!
+//! - `set NAME VALUE` &mdash; parses `VALUE` the same way as on the command
+//!   line, assigns it to the flag, and responds `ok`. Only flags included in
+//!   the allowlist passed to `serve` may be set.
+//!
+//! - `list` &mdash; responds with one line `NAME VALUE` per flag, or just
+//!   `NAME` for a flag without a value, followed by a line `ok`.
+//!
+//! Flag names may be given with or without the leading `--`. Any request that
+//! fails is answered with a single line `error MESSAGE`.
+//!
+//! Values are escaped so that each fits on one line: a newline is written as
+//! `\n`, a carriage return as `\r`, and a backslash as `\\`. The value in a
+//! `set` request is unescaped the same way, so a value received from `get` can
+//! be sent back unchanged.
+//!
+//! ```console
+//! $ echo 'set log_level debug' | nc -U /run/myserver/admin.sock
+//! ok
+//! ```
+//!
+//! Values set through the admin socket trigger callbacks registered with
+//! [`Flag::on_change`]. Every `set` permanently allocates memory for the new
+//! value, as described on [`FlagInfo::set`], so the socket is meant for an
+//! operator's occasional adjustments rather than for automated high-frequency
+//! updates.
+//!
+//! [`Flag::on_change`]: crate::Flag::on_change
+//! [`FlagInfo::set`]: crate::introspect::FlagInfo::set
+//!
+//! # Access
+//!
+//! The socket is created with mode 0600, so only the user that the process
+//! runs as (and root) can connect to it. Anyone who can connect can read every
+//! flag and set the flags in the allowlist. The socket is bound inside a
+//! private directory created next to `path` and only then moved into place, so
+//! it is never reachable before its mode is restricted. Place the socket in a
+//! directory that other users cannot write to, so that it cannot be replaced
+//! afterward.
+//!
+//! Connections are served one at a time. A client that sends nothing for
+//! 10 seconds is disconnected, so that an idle connection cannot lock out
+//! other clients indefinitely.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! gflags::define! {
+//!     --log_level = "info"
+//!     --max_qps: u32 = 100
+//! }
+//!
+//! fn main() -> std::io::Result<()> {
+//!     gflags::parse();
+//!     gflags::admin::serve("/run/myserver/admin.sock", &["log_level"])?;
+//!
+//!     /* ... */
+//! #   Ok(())
+//! }
+//! ```
+
+use crate::introspect::{self, FlagInfo};
+use std::ffi::OsString;
+use std::fs::{self, DirBuilder, Permissions};
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+/// Start serving the admin protocol on a Unix domain socket at `path`.
+///
+/// Connections are handled one at a time on a background thread, so this
+/// function returns as soon as the socket is bound. A stale socket left at `path` by a previous
+/// process is replaced, but a socket on which another process is still
+/// listening is an error.
+///
+/// Only flags whose long name or alias appears in `mutable` may be changed
+/// through `set`. Every flag may be read.
+pub fn serve<P: AsRef<Path>>(path: P, mutable: &[&str]) -> io::Result<()> {
+    let path = path.as_ref();
+    if let Ok(metadata) = fs::symlink_metadata(path) {
+        if metadata.file_type().is_socket() {
+            match UnixStream::connect(path) {
+                Ok(_) => {
+                    let msg = format!("admin socket {} is in use", path.display());
+                    return Err(io::Error::new(ErrorKind::AddrInUse, msg));
+                }
+                Err(err) if err.kind() == ErrorKind::ConnectionRefused => {
+                    fs::remove_file(path)?;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    let listener = bind_private(path)?;
+    let mutable: Vec<String> = mutable.iter().map(|name| (*name).to_owned()).collect();
+
+    thread::Builder::new()
+        .name("gflags-admin".to_owned())
+        .spawn(move || {
+            for stream in listener.incoming().flatten() {
+                handle(stream, &mutable);
+            }
+        })?;
+
+    Ok(())
+}
+
+// Bind the socket in a fresh directory accessible only to the current user,
+// restrict its mode, then rename it to `path`. Binding directly at `path`
+// would leave a window in which the socket has the umask's default mode.
+fn bind_private(path: &Path) -> io::Result<UnixListener> {
+    let file_name = path.file_name().ok_or_else(|| {
+        let msg = format!("admin socket path {} has no file name", path.display());
+        io::Error::new(ErrorKind::InvalidInput, msg)
+    })?;
+    let mut dir_name = OsString::from(".");
+    dir_name.push(file_name);
+    dir_name.push(format!(".{}.tmp", process::id()));
+    let dir = path.with_file_name(dir_name);
+    DirBuilder::new().mode(0o700).create(&dir)?;
+
+    let result = bind_in(&dir, path);
+    let _ = fs::remove_dir_all(&dir);
+    result
+}
+
+fn bind_in(dir: &Path, path: &Path) -> io::Result<UnixListener> {
+    let staging = dir.join("admin.sock");
+    let listener = UnixListener::bind(&staging)?;
+    fs::set_permissions(&staging, Permissions::from_mode(0o600))?;
+    fs::rename(&staging, path)?;
+    Ok(listener)
+}
+
+// Longest request line accepted, so that a client cannot make the server
+// buffer an unbounded amount of input.
+const MAX_REQUEST_LEN: usize = 64 * 1024;
+
+// How long a connection may go without completing a request or accepting a
+// response before it is dropped to let the next client in.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn handle(stream: UnixStream, mutable: &[String]) {
+    if stream.set_read_timeout(Some(IDLE_TIMEOUT)).is_err()
+        || stream.set_write_timeout(Some(IDLE_TIMEOUT)).is_err()
+    {
+        return;
+    }
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(_) => return,
+    };
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let limit = MAX_REQUEST_LEN as u64 + 1;
+        match (&mut reader).take(limit).read_line(&mut line) {
+            Ok(0) | Err(_) => return,
+            Ok(_) => {}
+        }
+        if line.len() > MAX_REQUEST_LEN {
+            let _ = writer.write_all(b"error request too long\n");
+            return;
+        }
+        let request = line.trim_end_matches('\n').trim_end_matches('\r');
+        let response = respond(request, mutable);
+        if writer.write_all(response.as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+fn respond(line: &str, mutable: &[String]) -> String {
+    let line = line.trim_start();
+    let (command, rest) = split_word(line);
+    match command {
+        "" => String::new(),
+        "get" => {
+            let (name, _rest) = split_word(rest);
+            match lookup(name) {
+                Ok(flag) => match flag.value() {
+                    Some(value) => format!("ok {}\n", escape(&value)),
+                    None => format!("error flag --{} has no value\n", flag.name()),
+                },
+                Err(err) => err,
+            }
+        }
+        "set" => {
+            let (name, value) = split_word(rest);
+            let flag = match lookup(name) {
+                Ok(flag) => flag,
+                Err(err) => return err,
+            };
+            let allowed = Some(&flag.name())
+                .into_iter()
+                .chain(flag.aliases())
+                .any(|name| mutable.iter().any(|allowed| allowed == name));
+            if !allowed {
+                return format!("error flag --{} is not mutable\n", flag.name());
+            }
+            let value = match unescape(value) {
+                Ok(value) => value,
+                Err(err) => return format!("error {}\n", err),
+            };
+            match flag.set(&value) {
+                Ok(()) => "ok\n".to_owned(),
+                Err(err) => format!("error failed to parse --{}: {}\n", flag.name(), err),
+            }
+        }
+        "list" => {
+            let mut response = String::new();
+            for flag in introspect::flags() {
+                response.push_str(flag.name());
+                if let Some(value) = flag.value() {
+                    response.push(' ');
+                    response.push_str(&escape(&value));
+                }
+                response.push('\n');
+            }
+            response.push_str("ok\n");
+            response
+        }
+        _ => format!("error unknown command `{}`\n", command),
+    }
+}
+
+fn lookup(name: &str) -> Result<FlagInfo, String> {
+    let name = name.strip_prefix("--").unwrap_or(name);
+    if name.is_empty() {
+        return Err("error missing flag name\n".to_owned());
+    }
+    introspect::find(name).ok_or_else(|| format!("error unknown flag --{}\n", name))
+}
+
+fn split_word(string: &str) -> (&str, &str) {
+    match string.find(' ') {
+        Some(i) => (&string[..i], &string[i + 1..]),
+        None => (string, ""),
+    }
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn unescape(value: &str) -> Result<String, String> {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            unescaped.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => unescaped.push('\\'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => return Err(format!("invalid escape `\\{}`", other)),
+            None => return Err("trailing backslash".to_owned()),
+        }
+    }
+    Ok(unescaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape, respond, split_word, unescape};
+
+    #[test]
+    fn split() {
+        assert_eq!(split_word("set name a b"), ("set", "name a b"));
+        assert_eq!(split_word("list"), ("list", ""));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(respond("frob", &[]), "error unknown command `frob`\n");
+        assert_eq!(respond("get", &[]), "error missing flag name\n");
+        assert_eq!(respond("get --nope", &[]), "error unknown flag --nope\n");
+        assert_eq!(respond("list", &[]), "ok\n");
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(escape("a\nb\\c\r"), "a\\nb\\\\c\\r");
+        assert_eq!(unescape("a\\nb\\\\c\\r").unwrap(), "a\nb\\c\r");
+        assert_eq!(unescape("a\\t").unwrap_err(), "invalid escape `\\t`");
+        assert_eq!(unescape("a\\").unwrap_err(), "trailing backslash");
+    }
+}
@@ -13,6 +13,10 @@ impl Arg {
         Arg { name, arg }
     }
 
+    pub(crate) fn name(&self) -> Name {
+        self.name
+    }
+
     /// Access the raw value given on the command line, which may not be legal
     /// UTF-8.
     pub fn get_raw(self) -> &'static OsStr {
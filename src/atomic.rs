@@ -19,16 +19,26 @@ impl<T> StaticAtomicPtr<T> {
     }
 
     pub fn load(&self, ordering: Ordering) -> &'static T {
-        let ptr = self.ptr.load(ordering);
-        let reference = unsafe { ptr.as_ref() };
-        match reference {
+        match self.try_load(ordering) {
             Some(t) => t,
             None => panic!("flag is not present"),
         }
     }
 
+    pub fn try_load(&self, ordering: Ordering) -> Option<&'static T> {
+        let ptr = self.ptr.load(ordering);
+        unsafe { ptr.as_ref() }
+    }
+
     pub fn store(&self, value: &'static T, ordering: Ordering) {
         let ptr = value as *const T as *mut T;
         self.ptr.store(ptr, ordering);
     }
+
+    pub fn store_option(&self, value: Option<&'static T>, ordering: Ordering) {
+        match value {
+            Some(value) => self.store(value, ordering),
+            None => self.ptr.store(ptr::null_mut(), ordering),
+        }
+    }
 }
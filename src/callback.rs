@@ -0,0 +1,53 @@
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+// Append-only list of callbacks. Nodes are leaked so that callbacks can be run
+// without holding any lock.
+pub struct Callbacks<T> {
+    head: AtomicPtr<Node<T>>,
+}
+
+struct Node<T> {
+    callback: Box<dyn Fn(&T) + Send + Sync>,
+    next: *const Node<T>,
+}
+
+impl<T> Callbacks<T> {
+    pub const fn new() -> Self {
+        Callbacks {
+            head: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    pub fn push(&self, callback: Box<dyn Fn(&T) + Send + Sync>) {
+        let node = Box::leak(Box::new(Node {
+            callback,
+            next: ptr::null(),
+        }));
+        let mut head = self.head.load(Ordering::Acquire);
+        loop {
+            node.next = head;
+            match self
+                .head
+                .compare_exchange_weak(head, node, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    pub fn call(&self, value: &T) {
+        let mut nodes = Vec::new();
+        let mut node = self.head.load(Ordering::Acquire) as *const Node<T>;
+        while let Some(current) = unsafe { node.as_ref() } {
+            nodes.push(current);
+            node = current.next;
+        }
+
+        // Run in order of registration.
+        for node in nodes.into_iter().rev() {
+            (node.callback)(value);
+        }
+    }
+}
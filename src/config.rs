@@ -0,0 +1,470 @@
+//! Loading flag values from a TOML or JSON config file.
+//!
+//! *This module is available only with the `config` Cargo feature.*
+//!
+//! A config file maps flag names to values. Each value is parsed by the flag's
+//! [`Value`] impl exactly as if it had been given on the command line, so any
+//! type usable as a flag works in a config file too. Booleans and numbers may
+//! be written as native TOML or JSON values, and arrays are joined with the
+//! separator of a [`List`] flag.
+//!
+//! ```toml
+//! log_level = "debug"
+//! max_qps = 250
+//! languages = ["english", "french"]
+//!
+//! # Nested tables are prefixes of the flag name, joined by `_`. This assigns
+//! # --server_port and --server_threads.
+//! [server]
+//! port = 8080
+//! threads = 16
+//! ```
+//!
+//! Flags may also be assigned from environment variables by [`load_env`]. The
+//! precedence from lowest to highest is: the defaults in [`gflags::define!`],
+//! the config file, the environment, the command line. This holds regardless
+//! of the order in which the sources are loaded. A flag already assigned by a
+//! higher source is left alone, so the config file may be loaded either before
+//! [`gflags::parse`] or after it, for example when the path of the config file
+//! is itself a flag.
+//!
+//! A file or environment containing any invalid value is rejected as a whole,
+//! without assigning any of its flags. So is a file that sets one flag twice,
+//! such as by its name and by an alias.
+//!
+//! When loaded after [`gflags::parse`], the new values are checked against
+//! every [`gflags::constraint!`] and [`gflags::on_parsed!`] hook again, since
+//! those already ran for the command line alone. If any of them fails, all of
+//! the new values are undone and the failures are returned as the error.
+//! [`Flag::on_change`] callbacks run only once the new values are accepted.
+//!
+//! This feature depends on the `toml` and `serde_json` crates and therefore
+//! requires a newer compiler than the rest of gflags, currently Rust 1.85.
+//!
+//! [`Value`]: crate::custom::Value
+//! [`List`]: crate::types::List
+//! [`gflags::define!`]: crate::define
+//! [`gflags::parse`]: crate::parse()
+//! [`gflags::constraint!`]: macro@crate::constraint
+//! [`gflags::on_parsed!`]: macro@crate::on_parsed
+//! [`Flag::on_change`]: crate::Flag::on_change
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use std::process;
+//!
+//! gflags::define! {
+//!     /// Path of the service config file.
+//!     --config = "/etc/myserver.toml"
+//!
+//!     --max_qps: u32 = 100
+//! }
+//!
+//! fn main() {
+//!     gflags::parse();
+//!
+//!     let loaded = gflags::config::load(CONFIG.flag)
+//!         .and_then(|()| gflags::config::load_env("MYSERVER_"));
+//!     if let Err(err) = loaded {
+//!         eprintln!("{}", err);
+//!         process::exit(1);
+//!     }
+//! }
+//! ```
+
+use crate::constraint;
+use crate::dispatch::Pending;
+use crate::error::{Error, Result};
+use crate::hook;
+use crate::name::Name;
+use crate::origin::Origin;
+use crate::parse;
+use crate::registry::Flag;
+use crate::state;
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::ffi::OsStr;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+enum Node {
+    Null,
+    Scalar(String),
+    Array(Vec<Node>),
+    // Each entry records the line it is on, counting from 1.
+    Table(Vec<(String, usize, Node)>),
+}
+
+/// Assign flags from the config file at `path`.
+///
+/// The format is chosen by the file extension, which must be `.toml` or
+/// `.json`. Keys that do not name a registered flag or one of its aliases are
+/// an error, as are values that fail to parse. Every problem in the file is
+/// included in the error message, one per line.
+pub fn load<P: AsRef<Path>>(path: P) -> Result<()> {
+    let path = path.as_ref();
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) => {
+            let msg = format!("failed to read config file {}: {}", path.display(), err);
+            return Err(Error::new(msg));
+        }
+    };
+
+    let root = match path.extension().and_then(OsStr::to_str) {
+        Some("toml") => from_toml(&contents),
+        Some("json") => from_json(&contents),
+        _ => {
+            let msg = format!(
+                "unsupported config file {}; expected a .toml or .json file",
+                path.display(),
+            );
+            return Err(Error::new(msg));
+        }
+    };
+    let entries = match root {
+        Ok(Node::Table(entries)) => entries,
+        Ok(_) => {
+            let msg = format!(
+                "config file {} must contain a table of flags",
+                path.display()
+            );
+            return Err(Error::new(msg));
+        }
+        Err(err) => {
+            let msg = format!("failed to parse config file {}: {}", path.display(), err);
+            return Err(Error::new(msg));
+        }
+    };
+
+    let longs = longs();
+    let mut assignments = Vec::new();
+    let mut errors = Vec::new();
+    resolve(entries, "", &longs, &mut assignments, &mut errors);
+    let mut keys = BTreeMap::new();
+    for &(key, _line, flag, _) in &assignments {
+        if let Some(previous) = keys.insert(flag.name, key) {
+            errors.push(format!(
+                "`{}` and `{}` both set --{}",
+                previous, key, flag.name
+            ));
+        }
+    }
+    if !errors.is_empty() {
+        return Err(report(path, errors));
+    }
+
+    let file: &'static Path = Box::leak(path.to_owned().into_boxed_path());
+    let mut warned = BTreeSet::new();
+    let mut pending = Vec::new();
+    for (key, line, flag, node) in assignments {
+        let flag = parse::check_deprecated(flag, Name::long(key), &longs, &mut warned);
+        match flag.parser.origin() {
+            Origin::CommandLine { .. } | Origin::Environment { .. } => continue,
+            _ => {}
+        }
+        let value = match render(node, flag) {
+            Ok(value) => value,
+            Err(err) => {
+                errors.push(format!("invalid value for `{}`: {}", key, err));
+                continue;
+            }
+        };
+        let value = Box::leak(value.into_boxed_str());
+        let name = Name::long(key);
+        let origin = Origin::Config { file, line };
+        match flag.parser.prepare_arg(name, OsStr::new(value), origin) {
+            Ok(assign) => pending.push(assign),
+            Err(err) => errors.push(format!("invalid value for `{}`: {}", key, err)),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(report(path, errors));
+    }
+
+    apply(pending).map_err(|errors| report(path, errors))
+}
+
+/// Assign flags from environment variables.
+///
+/// The variable for each flag is `prefix` followed by the flag's long name in
+/// uppercase with hyphens replaced by underscores. With a prefix of
+/// `MYSERVER_`, the flag `--max_qps` is read from `MYSERVER_MAX_QPS`. Aliases
+/// are not consulted. Values are parsed exactly as if they had been given on
+/// the command line, and any that fail to parse are included in the error
+/// message, one per line.
+pub fn load_env(prefix: &str) -> Result<()> {
+    let longs = longs();
+    let mut warned = BTreeSet::new();
+    let mut pending = Vec::new();
+    let mut errors = Vec::new();
+    for (&name, &flag) in &longs {
+        if name != flag.name {
+            continue;
+        }
+        let var = format!("{}{}", prefix, name.to_ascii_uppercase().replace('-', "_"));
+        let value = match env::var_os(&var) {
+            Some(value) => value,
+            None => continue,
+        };
+        let name = Name::long(name);
+        let flag = parse::check_deprecated(flag, name, &longs, &mut warned);
+        if let Origin::CommandLine { .. } = flag.parser.origin() {
+            continue;
+        }
+        let var: &'static str = Box::leak(var.into_boxed_str());
+        let value: &'static OsStr = Box::leak(value.into_boxed_os_str());
+        match flag
+            .parser
+            .prepare_arg(name, value, Origin::Environment { var })
+        {
+            Ok(assign) => pending.push(assign),
+            Err(err) => errors.push(format!(
+                "invalid value for environment variable `{}`: {}",
+                var, err,
+            )),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(Error::new(errors.join("\n")));
+    }
+
+    apply(pending).map_err(|errors| Error::new(errors.join("\n")))
+}
+
+// Assigns a batch of values. If gflags::parse has already finished, its
+// validation is repeated over the new values, and the whole batch is undone if
+// it breaks a constraint or hook.
+fn apply(mut pending: Vec<Box<dyn Pending>>) -> std::result::Result<(), Vec<String>> {
+    for value in &mut pending {
+        value.assign();
+    }
+
+    if state::parsing_finished() {
+        let mut errors = constraint::check();
+        if errors.is_empty() {
+            errors = hook::run();
+        }
+        if !errors.is_empty() {
+            for value in pending.iter_mut().rev() {
+                value.undo();
+            }
+            return Err(errors);
+        }
+    }
+
+    for value in &pending {
+        value.notify();
+    }
+    Ok(())
+}
+
+fn longs() -> BTreeMap<&'static str, &'static Flag> {
+    let mut longs = BTreeMap::new();
+    for flag in inventory::iter::<Flag> {
+        for &name in Some(&flag.name).into_iter().chain(flag.aliases) {
+            longs.insert(name, flag);
+        }
+    }
+    parse::check_forwarding(&longs);
+    longs
+}
+
+fn resolve(
+    entries: Vec<(String, usize, Node)>,
+    prefix: &str,
+    longs: &BTreeMap<&'static str, &'static Flag>,
+    assignments: &mut Vec<(&'static str, usize, &'static Flag, Node)>,
+    errors: &mut Vec<String>,
+) {
+    for (key, line, node) in entries {
+        let key = if prefix.is_empty() {
+            key
+        } else {
+            format!("{}_{}", prefix, key)
+        };
+        if let Node::Table(entries) = node {
+            resolve(entries, &key, longs, assignments, errors);
+            continue;
+        }
+        match longs.get(key.as_str()) {
+            Some(&flag) => {
+                let key = Box::leak(key.into_boxed_str());
+                assignments.push((key, line, flag, node));
+            }
+            None => match suggest(&key, longs) {
+                Some(suggestion) => errors.push(format!(
+                    "unknown flag `{}`; did you mean `{}`?",
+                    key, suggestion,
+                )),
+                None => errors.push(format!("unknown flag `{}`", key)),
+            },
+        }
+    }
+}
+
+// Converts a config value to the string that would be passed to the flag on
+// the command line.
+fn render(node: Node, flag: &Flag) -> std::result::Result<String, String> {
+    match node {
+        Node::Scalar(string) => Ok(string),
+        Node::Null => Err("null is not a flag value".to_owned()),
+        Node::Table(_) => unreachable!(),
+        Node::Array(items) => {
+            let separator = match flag.parser.separator() {
+                Some(separator) => separator,
+                None => return Err("flag does not accept a list of values".to_owned()),
+            };
+            let mut string = String::new();
+            for (i, item) in items.into_iter().enumerate() {
+                if i > 0 {
+                    string.push(separator);
+                }
+                match item {
+                    Node::Scalar(item) => string.push_str(&item),
+                    _ => return Err(format!("element {} is not a single value", i + 1)),
+                }
+            }
+            Ok(string)
+        }
+    }
+}
+
+fn report(path: &Path, errors: Vec<String>) -> Error {
+    let mut msg = String::new();
+    for (i, error) in errors.iter().enumerate() {
+        if i > 0 {
+            msg.push('\n');
+        }
+        let _ = write!(msg, "{}: {}", path.display(), error);
+    }
+    Error::new(msg)
+}
+
+// The most similar flag name, if any is close enough to be a plausible typo.
+fn suggest(key: &str, longs: &BTreeMap<&'static str, &'static Flag>) -> Option<&'static str> {
+    let threshold = (key.chars().count() / 3).max(1);
+    longs
+        .keys()
+        .map(|&name| (edit_distance(key, name), name))
+        .filter(|&(distance, _)| distance <= threshold)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, name)| name)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitute = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitute.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+fn from_toml(contents: &str) -> std::result::Result<Node, String> {
+    use toml::de::{DeTable, DeValue};
+
+    fn convert(contents: &str, value: DeValue) -> Node {
+        match value {
+            DeValue::String(string) => Node::Scalar(string.into_owned()),
+            DeValue::Integer(int) => match i64::from_str_radix(int.as_str(), int.radix()) {
+                Ok(int) => Node::Scalar(int.to_string()),
+                Err(_) => Node::Scalar(int.to_string()),
+            },
+            DeValue::Float(float) => match float.as_str().parse::<f64>() {
+                Ok(float) => Node::Scalar(float.to_string()),
+                Err(_) => Node::Scalar(float.to_string()),
+            },
+            DeValue::Boolean(boolean) => Node::Scalar(boolean.to_string()),
+            DeValue::Datetime(datetime) => Node::Scalar(datetime.to_string()),
+            DeValue::Array(array) => Node::Array(
+                array
+                    .into_iter()
+                    .map(|value| convert(contents, value.into_inner()))
+                    .collect(),
+            ),
+            DeValue::Table(table) => convert_table(contents, table),
+        }
+    }
+
+    fn convert_table(contents: &str, table: DeTable) -> Node {
+        Node::Table(
+            table
+                .into_iter()
+                .map(|(key, value)| {
+                    let line = line_of(contents, key.span().start);
+                    let value = convert(contents, value.into_inner());
+                    (key.into_inner().into_owned(), line, value)
+                })
+                .collect(),
+        )
+    }
+
+    match DeTable::parse(contents) {
+        Ok(table) => Ok(convert_table(contents, table.into_inner())),
+        Err(err) => Err(err.message().to_owned()),
+    }
+}
+
+fn from_json(contents: &str) -> std::result::Result<Node, String> {
+    use serde_json::value::RawValue;
+
+    // Values are deserialized as raw text first, which borrows from `contents`
+    // and so reveals the line that each one is on.
+    fn convert(contents: &str, raw: &RawValue) -> serde_json::Result<Node> {
+        let text = raw.get().trim_start();
+        if text.starts_with('{') {
+            let object: BTreeMap<String, &RawValue> = serde_json::from_str(text)?;
+            let mut entries = Vec::new();
+            for (key, value) in object {
+                let offset = value.get().as_ptr() as usize - contents.as_ptr() as usize;
+                entries.push((key, line_of(contents, offset), convert(contents, value)?));
+            }
+            Ok(Node::Table(entries))
+        } else if text.starts_with('[') {
+            let array: Vec<&RawValue> = serde_json::from_str(text)?;
+            let mut items = Vec::new();
+            for value in array {
+                items.push(convert(contents, value)?);
+            }
+            Ok(Node::Array(items))
+        } else {
+            Ok(match serde_json::from_str(text)? {
+                serde_json::Value::Bool(boolean) => Node::Scalar(boolean.to_string()),
+                serde_json::Value::Number(number) => Node::Scalar(number.to_string()),
+                serde_json::Value::String(string) => Node::Scalar(string),
+                _ => Node::Null,
+            })
+        }
+    }
+
+    serde_json::from_str::<&RawValue>(contents)
+        .and_then(|raw| convert(contents, raw))
+        .map_err(|err| err.to_string())
+}
+
+// Line number, counting from 1, of the byte at `offset` in `contents`.
+fn line_of(contents: &str, offset: usize) -> usize {
+    contents[..offset].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::edit_distance;
+
+    #[test]
+    fn distance() {
+        assert_eq!(edit_distance("max_qps", "max_qps"), 0);
+        assert_eq!(edit_distance("max_qsp", "max_qps"), 2);
+        assert_eq!(edit_distance("log_levl", "log_level"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+}
@@ -0,0 +1,51 @@
+use crate::dispatch::Parser;
+use crate::registry::{Constraint, ConstraintKind, Flag};
+
+// Returns a description of every registered constraint that is violated by the
+// flags given on the command line.
+pub(crate) fn check() -> Vec<String> {
+    let flags = inventory::iter::<Flag>.into_iter().collect::<Vec<_>>();
+    let find = |parser: &dyn Parser| -> &Flag {
+        match flags.iter().find(|flag| same(flag.parser, parser)) {
+            Some(flag) => flag,
+            None => panic!("gflags::constraint! refers to a flag that is not registered"),
+        }
+    };
+
+    let mut errors = Vec::new();
+    for constraint in inventory::iter::<Constraint> {
+        if !constraint.flag.is_present() {
+            continue;
+        }
+        let flag = find(constraint.flag);
+        for &other in constraint.others {
+            let other_flag = find(other);
+            match constraint.kind {
+                ConstraintKind::Requires if !other.is_present() => errors.push(format!(
+                    "Flag --{} ({}) requires --{} ({})",
+                    flag.name,
+                    location(flag),
+                    other_flag.name,
+                    location(other_flag),
+                )),
+                ConstraintKind::ConflictsWith if other.is_present() => errors.push(format!(
+                    "Flags --{} ({}) and --{} ({}) cannot be used together",
+                    flag.name,
+                    location(flag),
+                    other_flag.name,
+                    location(other_flag),
+                )),
+                _ => {}
+            }
+        }
+    }
+    errors
+}
+
+fn same(a: &dyn Parser, b: &dyn Parser) -> bool {
+    (a as *const dyn Parser).cast::<()>() == (b as *const dyn Parser).cast::<()>()
+}
+
+fn location(flag: &Flag) -> String {
+    format!("defined at {}:{}", flag.file, flag.line)
+}
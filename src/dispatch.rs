@@ -1,42 +1,165 @@
 use crate::arg::Arg;
-use crate::error::Error;
+use crate::error::{Error, Result};
 use crate::name::Name;
-use crate::state::Flag;
+use crate::origin::Origin;
+use crate::state::{Flag, Snapshot};
 use crate::token::Tokenizer;
 use crate::value::Value;
+use std::ffi::OsStr;
 use std::process;
 
 pub trait Parser: Sync {
     fn parse(&self, name: Name, command_line: &mut Tokenizer);
+    fn parse_arg(&self, name: Name, arg: &'static OsStr, origin: Origin);
+    fn try_parse_arg(&self, name: Name, arg: &'static OsStr, origin: Origin) -> Result<()>;
+    fn prepare_arg(
+        &'static self,
+        name: Name,
+        arg: &'static OsStr,
+        origin: Origin,
+    ) -> Result<Box<dyn Pending>>;
     fn is_bool(&self) -> bool;
-    fn unset_bool(&self);
+    fn is_present(&self) -> bool;
+    fn origin(&self) -> Origin;
+    fn unset_bool(&self, origin: Origin);
+    fn add_repeats(&self, extra: usize);
+    fn value(&self) -> Option<String>;
+    fn default(&self) -> Option<String>;
+    fn shows_default(&self) -> bool;
+    fn separator(&self) -> Option<char>;
 }
 
 impl<T: Value> Parser for Flag<T> {
     fn parse(&self, name: Name, command_line: &mut Tokenizer) {
+        let origin = Origin::CommandLine {
+            index: command_line.index(),
+        };
         if T::IS_BOOL {
+            self.set_origin(origin);
             T::set_bool(self, &true);
         } else {
             let arg = next_arg(name, command_line);
-            match T::parse(arg) {
-                Ok(value) => self.set(value),
-                Err(err) => parse_failed(name, err),
-            }
+            self.parse_arg(name, arg, origin);
         }
     }
 
+    fn parse_arg(&self, name: Name, arg: &'static OsStr, origin: Origin) {
+        if let Err(err) = self.try_parse_arg(name, arg, origin) {
+            parse_failed(name, err);
+        }
+    }
+
+    fn try_parse_arg(&self, name: Name, arg: &'static OsStr, origin: Origin) -> Result<()> {
+        let arg = Arg::new(name, arg);
+        if T::IS_BOOL {
+            let value = if bool::parse(arg)? { &true } else { &false };
+            self.set_origin(origin);
+            T::set_bool(self, value);
+        } else {
+            let value = T::parse(arg)?;
+            self.set_origin(origin);
+            self.set(value);
+        }
+        Ok(())
+    }
+
+    // Parses the value without assigning it yet, so that a batch of values can
+    // be validated before any of them takes effect.
+    fn prepare_arg(
+        &'static self,
+        name: Name,
+        arg: &'static OsStr,
+        origin: Origin,
+    ) -> Result<Box<dyn Pending>> {
+        let arg = Arg::new(name, arg);
+        let value = if T::IS_BOOL {
+            T::from_bool(bool::parse(arg)?)
+        } else {
+            T::parse(arg)?
+        };
+        Ok(Box::new(PendingValue {
+            flag: self,
+            value: Box::leak(Box::new(value)),
+            origin,
+            previous: None,
+        }))
+    }
+
     fn is_bool(&self) -> bool {
         T::IS_BOOL
     }
 
-    fn unset_bool(&self) {
+    fn is_present(&self) -> bool {
+        Flag::is_present(self)
+    }
+
+    fn origin(&self) -> Origin {
+        Flag::origin(self)
+    }
+
+    fn unset_bool(&self, origin: Origin) {
+        self.set_origin(origin);
         T::set_bool(self, &false);
     }
+
+    fn add_repeats(&self, extra: usize) {
+        Flag::add_repeats(self, extra);
+    }
+
+    fn value(&self) -> Option<String> {
+        self.current_value().and_then(T::render)
+    }
+
+    fn default(&self) -> Option<String> {
+        self.default_value().and_then(T::render)
+    }
+
+    fn shows_default(&self) -> bool {
+        T::SHOW_DEFAULT
+    }
+
+    fn separator(&self) -> Option<char> {
+        T::SEPARATOR
+    }
+}
+
+// A parsed value from a batch that is assigned as a whole. After assigning
+// every value in the batch, the caller either undoes all of them, or runs the
+// on_change callbacks of each.
+pub trait Pending {
+    fn assign(&mut self);
+    fn undo(&mut self);
+    fn notify(&self);
+}
+
+struct PendingValue<T: 'static> {
+    flag: &'static Flag<T>,
+    value: &'static T,
+    origin: Origin,
+    previous: Option<Snapshot<T>>,
+}
+
+impl<T> Pending for PendingValue<T> {
+    fn assign(&mut self) {
+        self.previous = Some(self.flag.snapshot());
+        self.flag.set_origin(self.origin);
+        self.flag.assign_quietly(self.value);
+    }
+
+    fn undo(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            self.flag.restore(previous);
+        }
+    }
+
+    fn notify(&self) {
+        self.flag.notify();
+    }
 }
 
-fn next_arg(name: Name, command_line: &mut Tokenizer) -> Arg {
+fn next_arg(name: Name, command_line: &mut Tokenizer) -> &'static OsStr {
     match command_line.next_arg() {
-        Some(arg) => Arg::new(name, arg),
+        Some(arg) => arg,
         None => {
             eprintln!("Missing value for `{}`", name);
             process::exit(1);
@@ -0,0 +1,191 @@
+//! Google-style `/flagz` page listing the current value of every flag.
+//!
+//! The page is rendered to a `String` so that it can be served from any HTTP
+//! server framework, or written anywhere else.
+//!
+//! # Examples
+//!
+//! ```
+//! use gflags::flagz::{self, Format};
+//!
+//! // Inside an HTTP handler for /flagz:
+//! let body = flagz::render(Format::Html);
+//! let content_type = Format::Html.content_type();
+//! # let _ = (body, content_type);
+//! ```
+
+use crate::introspect::{self, FlagInfo};
+use std::fmt::Write;
+
+/// Output format of [`render`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// One line per flag, like `--max_qps=250  (default: 100; modified)`.
+    Text,
+    /// A standalone HTML page containing a table of flags.
+    Html,
+    /// A JSON array with one object per flag, having keys `name`, `value`,
+    /// `default`, `modified` and `source`.
+    Json,
+}
+
+impl Format {
+    /// Value for the `Content-Type` header when serving the rendered page.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Format::Text => "text/plain; charset=utf-8",
+            Format::Html => "text/html; charset=utf-8",
+            Format::Json => "application/json",
+        }
+    }
+}
+
+/// Render every registered flag, including hidden ones, with its current
+/// value, default value, whether it has been modified from the default, and
+/// where its value came from as described by [`Origin`].
+///
+/// Values are rendered by each flag's [`Value::render`]. Flags whose type does
+/// not provide a rendering, or which have no value, are listed without one.
+/// Flags of type [`Secret`] are shown as `<redacted>`.
+///
+/// [`Value::render`]: crate::custom::Value::render
+/// [`Secret`]: crate::types::Secret
+/// [`Origin`]: crate::Origin
+pub fn render(format: Format) -> String {
+    let flags = introspect::flags();
+    match format {
+        Format::Text => render_text(&flags),
+        Format::Html => render_html(&flags),
+        Format::Json => render_json(&flags),
+    }
+}
+
+fn render_text(flags: &[FlagInfo]) -> String {
+    let mut out = String::new();
+    for flag in flags {
+        let _ = write!(out, "--{}", flag.name());
+        if let Some(value) = flag.value() {
+            let _ = write!(out, "={}", value);
+        }
+        let mut notes = Vec::new();
+        if let Some(default) = flag.default_value() {
+            notes.push(format!("default: {}", default));
+        }
+        if flag.is_present() {
+            notes.push("modified".to_owned());
+            notes.push(format!("source: {}", flag.origin()));
+        }
+        if !notes.is_empty() {
+            let _ = write!(out, "  ({})", notes.join("; "));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn render_html(flags: &[FlagInfo]) -> String {
+    let mut out = String::new();
+    out.push_str(
+        "<!DOCTYPE html>\n\
+         <html>\n\
+         <head><meta charset=\"utf-8\"><title>flagz</title></head>\n\
+         <body>\n\
+         <table>\n\
+         <tr><th>Flag</th><th>Value</th><th>Default</th><th>Modified</th><th>Source</th></tr>\n",
+    );
+    for flag in flags {
+        let value = flag.value().unwrap_or_default();
+        let default = flag.default_value().unwrap_or_default();
+        let modified = if flag.is_present() { "yes" } else { "no" };
+        let _ = writeln!(
+            out,
+            "<tr title=\"{}\"><td>--{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape_html(&flag.doc().join("\n")),
+            escape_html(flag.name()),
+            escape_html(&value),
+            escape_html(&default),
+            modified,
+            flag.origin(),
+        );
+    }
+    out.push_str("</table>\n</body>\n</html>\n");
+    out
+}
+
+fn render_json(flags: &[FlagInfo]) -> String {
+    let mut out = String::from("[");
+    for (i, flag) in flags.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("\n  {\"name\": ");
+        push_json_string(&mut out, flag.name());
+        out.push_str(", \"value\": ");
+        push_json_option(&mut out, flag.value());
+        out.push_str(", \"default\": ");
+        push_json_option(&mut out, flag.default_value());
+        let _ = write!(out, ", \"modified\": {}", flag.is_present());
+        out.push_str(", \"source\": ");
+        push_json_string(&mut out, &flag.origin().to_string());
+        out.push('}');
+    }
+    out.push_str("\n]\n");
+    out
+}
+
+fn escape_html(string: &str) -> String {
+    let mut out = String::with_capacity(string.len());
+    for ch in string.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+fn push_json_option(out: &mut String, string: Option<String>) {
+    match string {
+        Some(string) => push_json_string(out, &string),
+        None => out.push_str("null"),
+    }
+}
+
+fn push_json_string(out: &mut String, string: &str) {
+    out.push('"');
+    for ch in string.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if ch < ' ' => {
+                let _ = write!(out, "\\u{:04x}", ch as u32);
+            }
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_html, push_json_string};
+
+    #[test]
+    fn escape() {
+        assert_eq!(
+            escape_html("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+
+        let mut json = String::new();
+        push_json_string(&mut json, "a\"b\\c\nd\u{1}");
+        assert_eq!(json, "\"a\\\"b\\\\c\\nd\\u0001\"");
+    }
+}
@@ -1,3 +1,4 @@
+use crate::parse::ParseOptions;
 use crate::registry::Flag;
 use std::io::{self, Write};
 use std::process;
@@ -32,7 +33,7 @@ use std::process;
 /// documentation](index.html), the help text would be rendered as follows.
 ///
 /// ```text
-///         --big_menu
+///         --[no-]big_menu
 ///             Include 'advanced' options in the menu listing.
 ///
 ///     -f, --file
@@ -42,26 +43,82 @@ use std::process;
 ///             Comma-separated list of languages to offer in the 'lang' menu.
 /// ```
 ///
-/// The flags are listed in alphabetical order by long name.
+/// The flags are listed in alphabetical order by long name. Bool flags show the
+/// `--[no-]name` form that sets them to false unless they opt out of it with
+/// `#[no_negation]` or another flag is named `--no-name`. The default value of
+/// a `Duration` flag is shown, as in `[default: 30s]`.
+///
+/// Flags marked `#[hidden]` are left out. Use
+/// [`gflags::print_full_help_and_exit()`] to include them.
+///
+/// [`gflags::print_full_help_and_exit()`]: crate::print_full_help_and_exit
+///
+/// Help is rendered for the default [`ParseOptions`]. A program that parses
+/// with different options should print help through
+/// [`ParseOptions::print_help_and_exit`] instead.
 ///
 /// **Tip:** You will likely want to print your own content above this including
 /// the application name, version, author, introductory explanation, and usage
 /// strings.
 pub fn print_help_and_exit(code: i32) -> ! {
+    ParseOptions::new().print_help_and_exit(code);
+}
+
+/// Print the names and descriptions of all the flags, including hidden ones.
+///
+/// This is the same as [`gflags::print_help_and_exit()`] except that flags
+/// marked `#[hidden]` are included too, similar to `--helpfull` in C++ gflags.
+///
+/// [`gflags::print_help_and_exit()`]: crate::print_help_and_exit
+///
+/// # Example
+///
+/// ```
+/// gflags::define! {
+///     /// Print help for all flags, including internal ones.
+///     --helpfull = false
+///
+///     /// Dump internal state on exit.
+///     #[hidden]
+///     --debug_dump = false
+/// }
+///
+/// fn main() {
+///     gflags::parse();
+///     if HELPFULL.flag {
+///         gflags::print_full_help_and_exit(0);
+///     }
+///
+///     /* ... */
+/// }
+/// ```
+pub fn print_full_help_and_exit(code: i32) -> ! {
+    ParseOptions::new().print_full_help_and_exit(code);
+}
+
+pub(crate) fn help_and_exit(code: i32, full: bool, negation: bool) -> ! {
     if code == 0 {
-        let _ = try_print_help(&mut io::stdout().lock());
+        let _ = try_print_help(&mut io::stdout().lock(), full, negation);
     } else {
-        let _ = try_print_help(&mut io::stderr().lock());
+        let _ = try_print_help(&mut io::stderr().lock(), full, negation);
     };
 
     process::exit(code);
 }
 
-fn try_print_help(stream: &mut dyn Write) -> io::Result<()> {
-    let mut flags = inventory::iter::<Flag>.into_iter().collect::<Vec<_>>();
+fn try_print_help(stream: &mut dyn Write, full: bool, negation: bool) -> io::Result<()> {
+    let mut flags = inventory::iter::<Flag>
+        .into_iter()
+        .filter(|flag| full || !flag.hidden)
+        .collect::<Vec<_>>();
     flags.sort_by_key(|flag| flag.name);
 
     let has_short = flags.iter().any(|flag| flag.short.is_some());
+    let taken = |name: String| {
+        inventory::iter::<Flag>
+            .into_iter()
+            .any(|flag| flag.name == name || flag.aliases.contains(&name.as_str()))
+    };
 
     for flag in flags {
         write!(stream, "    ")?;
@@ -71,9 +128,20 @@ fn try_print_help(stream: &mut dyn Write) -> io::Result<()> {
                 None => write!(stream, "    ")?,
             }
         }
-        write!(stream, "--{}", flag.name)?;
+        if flag.parser.is_bool()
+            && flag.negatable
+            && negation
+            && !taken(format!("no-{}", flag.name))
+        {
+            write!(stream, "--[no-]{}", flag.name)?;
+        } else {
+            write!(stream, "--{}", flag.name)?;
+        }
         if let Some(placeholder) = flag.placeholder {
             write!(stream, " <{}>", placeholder)?;
+            if let Some(separator) = flag.parser.separator() {
+                write!(stream, "[{}<{}>...]", separator, placeholder)?;
+            }
         }
         writeln!(stream)?;
         for line in flag.doc {
@@ -84,6 +152,31 @@ fn try_print_help(stream: &mut dyn Write) -> io::Result<()> {
                 writeln!(stream, "            {}", line)?;
             }
         }
+        if let Some(deprecated) = &flag.deprecated {
+            match deprecated.note {
+                Some(note) => writeln!(stream, "            [deprecated: {}]", note)?,
+                None => writeln!(stream, "            [deprecated]")?,
+            }
+        }
+        if !flag.aliases.is_empty() || !flag.short_aliases.is_empty() {
+            write!(stream, "            [aliases: ")?;
+            let shorts = flag.short_aliases.iter().map(|short| format!("-{}", short));
+            let longs = flag.aliases.iter().map(|long| format!("--{}", long));
+            for (i, alias) in shorts.chain(longs).enumerate() {
+                if i > 0 {
+                    write!(stream, ", ")?;
+                }
+                write!(stream, "{}", alias)?;
+            }
+            writeln!(stream, "]")?;
+        }
+        if flag.parser.shows_default() {
+            if let Some(default) = flag.parser.default() {
+                if !default.is_empty() {
+                    writeln!(stream, "            [default: {}]", default)?;
+                }
+            }
+        }
         writeln!(stream)?;
     }
 
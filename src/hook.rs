@@ -0,0 +1,16 @@
+use crate::registry::Hook;
+
+// Runs every registered on_parsed hook in order, returning the error message of
+// each one that failed.
+pub(crate) fn run() -> Vec<String> {
+    let mut hooks = inventory::iter::<Hook>.into_iter().collect::<Vec<_>>();
+    hooks.sort_by_key(|hook| hook.order);
+
+    let mut errors = Vec::new();
+    for hook in hooks {
+        if let Err(err) = (hook.run)() {
+            errors.push(err.to_string());
+        }
+    }
+    errors
+}
@@ -0,0 +1,153 @@
+//! Runtime access to all registered flags by name.
+//!
+//! This is intended for tooling that operates on flags generically, such as
+//! admin endpoints that display or modify flags in a running process. Code that
+//! knows which flag it wants should access the flag's static directly instead.
+//!
+//! # Examples
+//!
+//! ```
+//! gflags::define! {
+//!     /// Maximum queries per second.
+//!     --max_qps: u32 = 100
+//! }
+//!
+//! fn main() {
+//!     gflags::parse();
+//!
+//!     let flag = gflags::introspect::find("max_qps").unwrap();
+//!     assert_eq!(flag.value().as_deref(), Some("100"));
+//!
+//!     flag.set("250").unwrap();
+//!     assert_eq!(MAX_QPS.flag, 250);
+//! }
+//! ```
+
+use crate::error::Result;
+use crate::name::Name;
+use crate::origin::Origin;
+use crate::registry;
+use std::ffi::OsStr;
+
+/// Information about one registered flag.
+#[derive(Copy, Clone)]
+pub struct FlagInfo {
+    flag: &'static registry::Flag,
+}
+
+/// All registered flags, including hidden ones, in alphabetical order by long
+/// name.
+pub fn flags() -> Vec<FlagInfo> {
+    let mut flags = inventory::iter::<registry::Flag>
+        .into_iter()
+        .map(|flag| FlagInfo { flag })
+        .collect::<Vec<_>>();
+    flags.sort_by_key(|info| info.flag.name);
+    flags
+}
+
+/// Look up a flag by its long name or one of its long aliases, given without
+/// the leading `--`.
+pub fn find(name: &str) -> Option<FlagInfo> {
+    inventory::iter::<registry::Flag>
+        .into_iter()
+        .find(|flag| flag.name == name || flag.aliases.contains(&name))
+        .map(|flag| FlagInfo { flag })
+}
+
+impl FlagInfo {
+    /// Long name of the flag, without the leading `--`.
+    pub fn name(&self) -> &'static str {
+        self.flag.name
+    }
+
+    /// Short name of the flag, if any.
+    pub fn short(&self) -> Option<char> {
+        self.flag.short
+    }
+
+    /// Additional long names of the flag.
+    pub fn aliases(&self) -> &'static [&'static str] {
+        self.flag.aliases
+    }
+
+    /// Lines of the flag's doc comment.
+    pub fn doc(&self) -> &'static [&'static str] {
+        self.flag.doc
+    }
+
+    /// Value placeholder shown in help text, like `LANG` for `<LANG>`.
+    pub fn placeholder(&self) -> Option<&'static str> {
+        self.flag.placeholder
+    }
+
+    /// Whether the flag is a bool switch.
+    pub fn is_bool(&self) -> bool {
+        self.flag.parser.is_bool()
+    }
+
+    /// Whether the flag is marked `#[hidden]`.
+    pub fn is_hidden(&self) -> bool {
+        self.flag.hidden
+    }
+
+    /// Whether the flag is marked `#[deprecated]`.
+    pub fn is_deprecated(&self) -> bool {
+        self.flag.deprecated.is_some()
+    }
+
+    /// Whether the flag has been assigned, on the command line or at runtime.
+    pub fn is_present(&self) -> bool {
+        self.flag.parser.is_present()
+    }
+
+    /// Where the current value of the flag came from.
+    pub fn origin(&self) -> Origin {
+        self.flag.parser.origin()
+    }
+
+    /// Current value of the flag, as rendered by its [`Value`] impl.
+    ///
+    /// Returns `None` if the flag has no value or its type does not provide a
+    /// rendering.
+    ///
+    /// [`Value`]: crate::custom::Value
+    pub fn value(&self) -> Option<String> {
+        self.flag.parser.value()
+    }
+
+    /// Default value of the flag, as rendered by its [`Value`] impl.
+    ///
+    /// [`Value`]: crate::custom::Value
+    pub fn default_value(&self) -> Option<String> {
+        self.flag.parser.default()
+    }
+
+    /// Source file containing the flag's definition, and line of the
+    /// `gflags::define!` invocation within that file.
+    pub fn location(&self) -> (&'static str, u32) {
+        (self.flag.file, self.flag.line)
+    }
+
+    /// Parse `value` in the same way as on the command line and assign it to
+    /// the flag.
+    ///
+    /// The flag's [`origin`] becomes [`Origin::Runtime`]. Callbacks registered
+    /// with [`Flag::on_change`] are run if parsing of the command line has
+    /// already finished.
+    ///
+    /// Flag values live for the rest of the process, so each call leaks a copy
+    /// of `value` (which types like `&str` borrow from) and the newly parsed
+    /// value. This is negligible for occasional changes but grows without
+    /// bound if a long-running process sets flags continually.
+    ///
+    /// [`origin`]: FlagInfo::origin
+    /// [`Flag::on_change`]: crate::Flag::on_change
+    pub fn set(&self, value: &str) -> Result<()> {
+        let value = Box::leak(value.to_owned().into_boxed_str());
+        let name = Name::long(self.flag.name);
+        self.flag
+            .parser
+            .try_parse_arg(name, OsStr::new(value), Origin::Runtime)
+    }
+}
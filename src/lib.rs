@@ -78,13 +78,24 @@
 //! ```
 //!
 //! Flags are required to have a long name (like `--verbose`) and may optionally
-//! have a short name (like `-v`). Flags must have exactly one long name and at
-//! most one short name; multiple different aliases for the same flag is not
-//! supported.
+//! have a short name (like `-v`). Additional long and short names may be listed
+//! after the first ones as aliases, as in `--max-conns, --max_connections`. The
+//! first long name is the one through which the flag is accessed in code and
+//! the one shown in help text, with aliases listed beneath it.
 //!
 //! Flags of a type other than bool may have an optional value-placeholder like
 //! `<LANG>`. This is optional and purely cosmetic. It appears in help text.
 //!
+//! Boolean flags are set to true by `--name` on the command line and to false by
+//! `--noname` or `--no-name`. The value may also be written explicitly as
+//! `--name=true` or `--name=false`, as well as `yes`/`no` or `1`/`0`. A bool
+//! flag with a short name also accepts `-n3` as a shorthand for repeating it
+//! three times as `-nnn`.
+//!
+//! An equal sign after a short flag is part of its value, so `-f=foo` gives
+//! `=foo`, unless [`ParseOptions::short_equals`] is enabled. Then `-f=foo`
+//! gives `foo`, and a bool flag also accepts `-n=false`.
+//!
 //! # Accessing flags
 //!
 //! Somewhere early in your application, call [`gflags::parse()`] to parse the
@@ -141,6 +152,28 @@
 //! }
 //! ```
 //!
+//! Alternatively, give the flag an `Option` type. An `Option` flag without a
+//! default value is `None` when not provided on the command line, so accessing
+//! `.flag` never panics.
+//!
+//! ```
+//! use std::path::Path;
+//!
+//! gflags::define! {
+//!     /// Search for patterns from the given file, with one pattern per line.
+//!     -f, --file: Option<&Path>
+//! }
+//!
+//! fn main() {
+//!     let patterns = gflags::parse();
+//!
+//!     match FILE.flag {
+//!         Some(path) => println!("searching for patterns from file: {}", path.display()),
+//!         None => println!("searching for patterns given on command line: {:?}", patterns),
+//!     }
+//! }
+//! ```
+//!
 //! # Printing help
 //!
 //! There is no built-in `-h` flag for help, but you can define your own and
@@ -168,7 +201,7 @@
 //! would be rendered as follows.
 //!
 //! ```text
-//!         --big_menu
+//!         --[no-]big_menu
 //!             Include 'advanced' options in the menu listing.
 //!
 //!     -f, --file
@@ -227,7 +260,8 @@
     clippy::must_use_candidate,
     clippy::needless_doctest_main,
     clippy::needless_pass_by_value,
-    clippy::single_match_else
+    clippy::single_match_else,
+    clippy::uninlined_format_args
 )]
 
 macro_rules! eprintln {
@@ -239,20 +273,34 @@ macro_rules! eprintln {
 
 mod arg;
 mod atomic;
+mod callback;
+mod constraint;
 mod dispatch;
 mod error;
 mod help;
+mod hook;
 mod name;
+mod origin;
 mod parse;
 mod state;
 mod token;
 mod value;
+mod warn;
 
+#[cfg(all(feature = "admin", unix))]
+pub mod admin;
+#[cfg(feature = "config")]
+pub mod config;
 pub mod custom;
+pub mod flagz;
+pub mod introspect;
+pub mod types;
 
-pub use crate::help::print_help_and_exit;
-pub use crate::parse::{parse, parse_os};
+pub use crate::help::{print_full_help_and_exit, print_help_and_exit};
+pub use crate::origin::Origin;
+pub use crate::parse::{parse, parse_os, ParseOptions};
 pub use crate::state::Flag;
+pub use crate::warn::set_warning_handler;
 
 // Not public API.
 #[doc(hidden)]
@@ -286,6 +334,9 @@ pub use gflags_impl as r#impl;
 ///
 /// - Long name for the flag, like `--verbose`. Long name is mandatory.
 ///
+/// - Optional aliases, each preceded by a comma, like `, --verbosity, -V`.
+///   These may be used on the command line in place of the names above.
+///
 /// - Optional value-placeholder in angle brackets, like `<FILE>`. This is
 ///   cosmetic and appears in generated help text.
 ///
@@ -295,6 +346,35 @@ pub use gflags_impl as r#impl;
 ///
 /// - Optional default value preceded by equal-sign: `= "default"`.
 ///
+/// Before the doc comments or between them, the flag may be marked
+/// `#[deprecated]` or `#[deprecated(note = "use --new_name")]`. Using a
+/// deprecated flag on the command line prints a warning the first time it
+/// occurs (see [`gflags::set_warning_handler`]), and the flag is marked as
+/// deprecated in help text. With `#[deprecated(forward_to = "new_name")]` the
+/// value given to the deprecated flag is assigned to the flag `--new_name`
+/// instead, so that a flag can be renamed without breaking existing command
+/// lines.
+///
+/// [`gflags::set_warning_handler`]: crate::set_warning_handler
+///
+/// A flag marked `#[hidden]` works like any other flag on the command line but
+/// is left out of [`gflags::print_help_and_exit()`]. This is useful for
+/// internal debugging flags not meant for end users. Hidden flags are still
+/// listed by [`gflags::print_full_help_and_exit()`].
+///
+/// [`gflags::print_help_and_exit()`]: crate::print_help_and_exit
+/// [`gflags::print_full_help_and_exit()`]: crate::print_full_help_and_exit
+///
+/// A bool flag marked `#[no_negation]` cannot be set to false by `--noname` or
+/// `--no-name`. Negation is also skipped for a spelling that is the name of
+/// another flag, so with a flag `--notify` alongside a bool flag `--tify`,
+/// `--notify` always refers to the former.
+///
+/// A single invocation may define any number of flags one after another, each
+/// with its own doc comments and visibility. Flags may optionally be separated
+/// by semicolons, which is necessary only if a default value expression would
+/// otherwise run into the next flag, as in `= a - -b`.
+///
 /// Invocation containing as few of the above as possible:
 ///
 /// ```
@@ -332,6 +412,23 @@ pub use gflags_impl as r#impl;
 /// #
 /// # fn main() {}
 /// ```
+///
+/// Several flags in one invocation:
+///
+/// ```
+/// gflags::define! {
+///     /// Address to listen on.
+///     --host = "127.0.0.1"
+///
+///     /// Port to listen on.
+///     pub -p, --port: u16 = 8080
+///
+///     /// Serve over TLS.
+///     --tls: bool
+/// }
+/// #
+/// # fn main() {}
+/// ```
 #[macro_export]
 macro_rules! define {
     ($($flag:tt)*) => {
@@ -340,3 +437,125 @@ macro_rules! define {
         }
     };
 }
+
+/// Declare that flags, possibly defined in different modules, must or must not
+/// be used together.
+///
+/// Each constraint names a flag static (as declared by [`gflags::define!`]),
+/// followed by `requires` or `conflicts_with`, followed by one or more other
+/// flag statics separated by commas. Multiple constraints in one invocation are
+/// separated by semicolons.
+///
+/// - `A requires B, C` &mdash; if `A` is given on the command line then `B` and
+///   `C` must be given too.
+///
+/// - `A conflicts_with B, C` &mdash; if `A` is given on the command line then
+///   neither `B` nor `C` may be given.
+///
+/// Like flags themselves, constraints may be declared from any source file.
+/// They are checked by [`gflags::parse()`] after all flags have been assigned.
+/// If any is violated, the process exits with an error message naming the
+/// offending flags and where each one was defined.
+///
+/// [`gflags::define!`]: macro.define.html
+/// [`gflags::parse()`]: fn.parse.html
+///
+/// # Examples
+///
+/// ```
+/// # mod input {
+/// #     gflags::define! {
+/// #         pub --file: &std::path::Path
+/// #     }
+/// # }
+/// #
+/// use std::path::Path;
+///
+/// gflags::define! {
+///     /// Certificate chain to serve.
+///     --tls-cert: &Path
+///
+///     /// Private key of the certificate.
+///     --tls-key: &Path
+///
+///     /// Read input from stdin.
+///     --stdin = false
+/// }
+///
+/// gflags::constraint! {
+///     TLS_CERT requires TLS_KEY;
+///     TLS_KEY requires TLS_CERT;
+///     STDIN conflicts_with input::FILE;
+/// }
+/// #
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! constraint {
+    ($($constraint:tt)*) => {
+        gflags::r#impl::constraint_impl! {
+            $($constraint)*
+        }
+    };
+}
+
+/// Register a function to validate flags after they have all been parsed.
+///
+/// The function must have signature `fn() -> gflags::custom::Result<()>`. It is
+/// run by [`gflags::parse()`] after every flag has been assigned and every
+/// [`gflags::constraint!`] has been checked, so it may look at the values of
+/// any flags to check conditions involving several of them together. Hooks
+/// may be registered from any source file.
+///
+/// All hooks run even if some fail. The errors of all failing hooks are
+/// printed together and then the process exits. Hooks are not run at all if a
+/// constraint is violated.
+///
+/// By default hooks run in an unspecified order. An ordering hint may be given
+/// as `order = N`; hooks with a lower number run first, and the default is 0.
+///
+/// [`gflags::parse()`]: fn.parse.html
+/// [`gflags::constraint!`]: macro.constraint.html
+///
+/// # Examples
+///
+/// ```
+/// use gflags::custom::{Error, Result};
+///
+/// gflags::define! {
+///     --min_workers: u32 = 1
+///     --max_workers: u32 = 16
+/// }
+///
+/// gflags::on_parsed!(check_workers);
+///
+/// fn check_workers() -> Result<()> {
+///     if MIN_WORKERS.flag > MAX_WORKERS.flag {
+///         return Err(Error::new("--min_workers must not exceed --max_workers"));
+///     }
+///     Ok(())
+/// }
+///
+/// gflags::on_parsed!(report_config, order = 100);
+///
+/// fn report_config() -> Result<()> {
+///     eprintln!("using up to {} workers", MAX_WORKERS.flag);
+///     Ok(())
+/// }
+/// #
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! on_parsed {
+    ($hook:path) => {
+        gflags::on_parsed!($hook, order = 0);
+    };
+    ($hook:path, order = $order:expr) => {
+        gflags::inventory::submit! {
+            gflags::registry::Hook {
+                run: $hook,
+                order: $order,
+            }
+        }
+    };
+}
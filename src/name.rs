@@ -23,6 +23,14 @@ impl Name {
             style: Style::Long(name),
         }
     }
+
+    // The name without its leading dashes, as it would be listed in --undefok.
+    pub(crate) fn bare(&self) -> String {
+        match self.style {
+            Style::Short(ch) => ch.to_string(),
+            Style::Long(name) => name.to_owned(),
+        }
+    }
 }
 
 impl Display for Name {
@@ -0,0 +1,90 @@
+use std::fmt::{self, Display};
+use std::path::Path;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::{PoisonError, RwLock};
+
+/// Where the current value of a flag came from.
+///
+/// Returned by [`Flag::origin`] and [`FlagInfo::origin`].
+///
+/// [`Flag::origin`]: crate::Flag::origin
+/// [`FlagInfo::origin`]: crate::introspect::FlagInfo::origin
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Origin {
+    /// The flag has not been assigned and holds its default value, if any.
+    Default,
+    /// The flag was assigned on the command line, by the argument at the given
+    /// position in `std::env::args_os()`. Position 0 is the executable.
+    CommandLine { index: usize },
+    /// The flag was assigned by a config file loaded through
+    /// `gflags::config::load`, on the given line counting from 1.
+    Config { file: &'static Path, line: usize },
+    /// The flag was assigned by an environment variable read through
+    /// `gflags::config::load_env`.
+    Environment { var: &'static str },
+    /// The flag was assigned at runtime after the command line was parsed,
+    /// such as through [`FlagInfo::set`].
+    ///
+    /// [`FlagInfo::set`]: crate::introspect::FlagInfo::set
+    Runtime,
+}
+
+impl Display for Origin {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Origin::Default => formatter.write_str("default"),
+            Origin::CommandLine { index } => write!(formatter, "command line (argv[{}])", index),
+            Origin::Config { file, line } => {
+                write!(formatter, "config file {}:{}", file.display(), line)
+            }
+            Origin::Environment { var } => write!(formatter, "environment variable {}", var),
+            Origin::Runtime => formatter.write_str("runtime"),
+        }
+    }
+}
+
+// The origin of a flag's current value. The lock is allocated by the first
+// assignment and reused by every later one, because a lock cannot be created in
+// a const fn before Rust 1.63.
+pub(crate) struct OriginCell {
+    lock: AtomicPtr<RwLock<Origin>>,
+}
+
+impl OriginCell {
+    pub(crate) const fn new() -> Self {
+        OriginCell {
+            lock: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    pub(crate) fn get(&self) -> Origin {
+        let lock = self.lock.load(Ordering::Acquire);
+        match unsafe { lock.as_ref() } {
+            Some(lock) => *lock.read().unwrap_or_else(PoisonError::into_inner),
+            None => Origin::Default,
+        }
+    }
+
+    pub(crate) fn set(&self, origin: Origin) {
+        let mut lock = self.lock.load(Ordering::Acquire);
+        if lock.is_null() {
+            let new = Box::into_raw(Box::new(RwLock::new(origin)));
+            match self.lock.compare_exchange(
+                ptr::null_mut(),
+                new,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return,
+                Err(existing) => {
+                    drop(unsafe { Box::from_raw(new) });
+                    lock = existing;
+                }
+            }
+        }
+        let lock = unsafe { &*lock };
+        *lock.write().unwrap_or_else(PoisonError::into_inner) = origin;
+    }
+}
@@ -1,9 +1,17 @@
 use crate::name::Name;
+use crate::origin::Origin;
 use crate::registry::Flag;
-use crate::token::{Token, Tokenizer};
-use std::collections::BTreeMap;
+use crate::state;
+use crate::token::{Syntax, Token, Tokenizer};
+use crate::warn;
+use crate::{constraint, help, hook};
+use std::collections::{BTreeMap, BTreeSet};
 use std::ffi::OsStr;
+use std::ops::{Bound, Range};
 use std::process;
+use std::ptr;
+
+const UNDEFOK: &str = "undefok";
 
 /// Initialize the value of all flags based on arguments from the command line
 /// at runtime.
@@ -36,6 +44,44 @@ use std::process;
 /// }
 /// ```
 ///
+/// # Short flags
+///
+/// Short flags may be clustered, as in `-xvf`. A short flag that takes a value
+/// accepts it as the next argument (`-f path`) or attached directly (`-fpath`),
+/// in which case an equal sign is part of the value: `-f=path` gives `=path`.
+///
+/// With [`ParseOptions::short_equals`], the value may instead be attached with
+/// an equal sign, so `-f=path` gives `path`. A bool flag's value is then
+/// optional and can only be given this way: `-v` means true, while `-v=false`
+/// sets the flag to false explicitly.
+///
+/// Digits following a bool short flag are a repeat count, so `-v3` is the same
+/// as `-vvv` and makes the flag's [`repeat_count()`] 3.
+///
+/// [`repeat_count()`]: crate::Flag::repeat_count
+///
+/// # Unknown flags
+///
+/// The command line may contain `--undefok=NAME,...` listing flags that are
+/// silently ignored if the application does not define them. This allows one
+/// launcher to pass the same command line to different versions of a binary,
+/// some of which do not know about a newer flag. An ignored flag may be given
+/// as `--name`, `--noname`, `--name=value` or `--name value`, and an unknown
+/// short flag `-x` is ignored if `x` is listed.
+///
+/// Since there is no telling whether an unknown flag takes a value, the
+/// argument after an ignored flag is assumed to be its value and is ignored
+/// too, unless that argument begins with `-`. So an ignorable bool flag must
+/// not be directly followed by a positional argument; write it as
+/// `--name=true` in that position. For an unknown short flag in a cluster like
+/// `-xvalue`, the rest of the cluster is its value. Note that this differs from
+/// C++ gflags and from earlier versions of this crate, where `bar` in
+/// `--undefok=foo --foo bar` is returned as a positional argument.
+///
+/// `--undefok` may come before or after the flags it names, because the
+/// command line is scanned for it before any flag is parsed. An unknown flag
+/// not covered by it is reported as soon as it is reached.
+///
 /// # Aborts
 ///
 /// Aborts the process with an error message if the command line does not
@@ -45,14 +91,7 @@ use std::process;
 ///
 /// [`gflags::parse_os`]: crate::parse_os()
 pub fn parse() -> Vec<&'static str> {
-    fn to_str_or_abort(os_str: &OsStr) -> &str {
-        os_str.to_str().unwrap_or_else(|| {
-            eprintln!("Unsupported non-UTF8 command line argument");
-            process::exit(1);
-        })
-    }
-
-    parse_os().into_iter().map(to_str_or_abort).collect()
+    ParseOptions::new().parse()
 }
 
 /// Initialize the value of all flags, accepting non-UTF8 positional arguments.
@@ -63,71 +102,639 @@ pub fn parse() -> Vec<&'static str> {
 ///
 /// [`gflags::parse`]: crate::parse()
 pub fn parse_os() -> Vec<&'static OsStr> {
-    let mut shorts = BTreeMap::new();
-    let mut longs = BTreeMap::new();
-    for flag in inventory::iter::<Flag> {
-        if let Some(short) = flag.short {
-            shorts.insert(short, flag);
+    ParseOptions::new().parse_os()
+}
+
+/// Options for customizing how the command line is parsed.
+///
+/// [`gflags::parse()`] is equivalent to `ParseOptions::new().parse()`.
+///
+/// [`gflags::parse()`]: crate::parse()
+///
+/// # Examples
+///
+/// ```
+/// gflags::define! {
+///     --verbose = false
+/// }
+///
+/// fn main() {
+///     let args = gflags::ParseOptions::new()
+///         .allow_unknown(&["experimental_cache"])
+///         .parse();
+///
+///     println!("args = {:?}", args);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ParseOptions {
+    allow_unknown: Vec<String>,
+    pass_through_unknown: bool,
+    stop_at_first_positional: bool,
+    double_dash: bool,
+    cluster_shorts: bool,
+    negation: bool,
+    single_dash_long: bool,
+    short_equals: bool,
+    abbreviations: bool,
+}
+
+impl ParseOptions {
+    /// Options matching the behavior of [`gflags::parse()`].
+    ///
+    /// [`gflags::parse()`]: crate::parse()
+    pub fn new() -> Self {
+        ParseOptions {
+            allow_unknown: Vec::new(),
+            pass_through_unknown: false,
+            stop_at_first_positional: false,
+            double_dash: true,
+            cluster_shorts: true,
+            negation: true,
+            single_dash_long: false,
+            short_equals: false,
+            abbreviations: false,
         }
-        longs.insert(flag.name, flag);
     }
 
-    let mut args = Vec::new();
-    let mut tokens = Tokenizer::new();
+    /// Silently ignore the given flags, named without the leading `--`, if
+    /// the application does not define them.
+    ///
+    /// This has the same effect as passing `--undefok` with these names on the
+    /// command line. See [`gflags::parse()`] for which forms of an unknown
+    /// flag are ignored.
+    ///
+    /// [`gflags::parse()`]: crate::parse()#unknown-flags
+    #[must_use]
+    pub fn allow_unknown(mut self, names: &[&str]) -> Self {
+        self.allow_unknown
+            .extend(names.iter().map(|name| (*name).to_owned()));
+        self
+    }
 
-    while let Some(token) = tokens.next() {
-        match token {
-            Token::Short(ch) => match shorts.get(&ch) {
-                Some(flag) => {
-                    let name = Name::short(ch);
-                    flag.parser.parse(name, &mut tokens);
-                }
-                None => {
-                    eprintln!("Unrecognized flag: -{}", ch);
-                    process::exit(1);
+    /// Return unrecognized flags among the positional arguments instead of
+    /// aborting.
+    ///
+    /// This is for programs that wrap another tool and forward to it whatever
+    /// flags they do not understand themselves. Each unrecognized flag is
+    /// returned with its original spelling, such as `--name`, `--name=value`
+    /// or `--noname`, in its original position relative to the positional
+    /// arguments. A value given to an unrecognized flag as a separate argument,
+    /// as in `--name value`, directly follows the flag in the returned vector.
+    ///
+    /// An unrecognized short flag is returned together with the rest of its
+    /// cluster, because the characters after it may be its value. For example
+    /// if `-v` is defined and `-o` is not, then `-vofile` sets `-v` and returns
+    /// `-ofile`.
+    ///
+    /// A `--` separator is returned too, followed by everything after it, so
+    /// that the wrapped tool sees the same separator.
+    ///
+    /// Flags named by `--undefok` or [`allow_unknown`] are dropped rather than
+    /// returned, together with the value that follows them as described under
+    /// [Unknown flags]. They are meant for this program, not the wrapped tool.
+    ///
+    /// [`allow_unknown`]: ParseOptions::allow_unknown
+    /// [Unknown flags]: crate::parse()#unknown-flags
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::process::Command;
+    ///
+    /// gflags::define! {
+    ///     /// Print the command before running it.
+    ///     --echo = false
+    /// }
+    ///
+    /// fn main() {
+    ///     let args = gflags::ParseOptions::new()
+    ///         .pass_through_unknown(true)
+    ///         .parse();
+    ///
+    ///     if ECHO.flag {
+    ///         println!("cargo {}", args.join(" "));
+    ///     }
+    ///     # return;
+    ///     Command::new("cargo").args(args).status().unwrap();
+    /// }
+    /// ```
+    #[must_use]
+    pub fn pass_through_unknown(mut self, enabled: bool) -> Self {
+        self.pass_through_unknown = enabled;
+        self
+    }
+
+    /// Treat every argument after the first positional argument as positional,
+    /// as POSIX `getopt` does. Disabled by default, in which case flags and
+    /// positional arguments may be interleaved.
+    #[must_use]
+    pub fn stop_at_first_positional(mut self, enabled: bool) -> Self {
+        self.stop_at_first_positional = enabled;
+        self
+    }
+
+    /// Treat every argument after `--` as positional. Enabled by default. When
+    /// disabled, `--` is itself returned as a positional argument.
+    #[must_use]
+    pub fn double_dash(mut self, enabled: bool) -> Self {
+        self.double_dash = enabled;
+        self
+    }
+
+    /// Accept several short flags cuddled in one argument, as in `-abc` for
+    /// `-a -b -c`, and a short flag's value attached to it, as in `-ofile`.
+    /// Enabled by default. When disabled, each short flag must be a separate
+    /// argument.
+    #[must_use]
+    pub fn cluster_shorts(mut self, enabled: bool) -> Self {
+        self.cluster_shorts = enabled;
+        self
+    }
+
+    /// Accept `--noname` and `--no-name` to set a bool flag `--name` to false.
+    /// Enabled by default. Individual flags may opt out of negation with a
+    /// `#[no_negation]` attribute in `gflags::define!`.
+    #[must_use]
+    pub fn negation(mut self, enabled: bool) -> Self {
+        self.negation = enabled;
+        self
+    }
+
+    /// Accept long flags written with a single dash, as in `-verbose` or
+    /// `-level=3`, as C++ gflags does. Disabled by default. When enabled, only
+    /// a dash followed by exactly one character is a short flag, so short flags
+    /// can no longer be clustered.
+    #[must_use]
+    pub fn single_dash_long(mut self, enabled: bool) -> Self {
+        self.single_dash_long = enabled;
+        self
+    }
+
+    /// Accept a value attached to a short flag with an equal sign, as in
+    /// `-f=path`, and an explicit value for a bool short flag, as in
+    /// `-v=false`. Disabled by default, in which case the equal sign is part of
+    /// the value, so `-f=path` gives the value `=path` as in earlier versions.
+    ///
+    /// This also applies with [`single_dash_long`], where `-f=path` would
+    /// otherwise be the long flag `--f`.
+    ///
+    /// [`single_dash_long`]: ParseOptions::single_dash_long
+    #[must_use]
+    pub fn short_equals(mut self, enabled: bool) -> Self {
+        self.short_equals = enabled;
+        self
+    }
+
+    /// Accept any unambiguous prefix of a long flag's name in place of the
+    /// full name, as in `--verb` for `--verbose`, like GNU `getopt_long`.
+    /// Disabled by default. A prefix shared by more than one flag is an error
+    /// listing the candidates, and an exact name always takes precedence over
+    /// a longer name it is a prefix of.
+    #[must_use]
+    pub fn abbreviations(mut self, enabled: bool) -> Self {
+        self.abbreviations = enabled;
+        self
+    }
+
+    /// Initialize the value of all flags, as by [`gflags::parse()`].
+    ///
+    /// [`gflags::parse()`]: crate::parse()
+    pub fn parse(&self) -> Vec<&'static str> {
+        fn to_str_or_abort(os_str: &OsStr) -> &str {
+            os_str.to_str().unwrap_or_else(|| {
+                eprintln!("Unsupported non-UTF8 command line argument");
+                process::exit(1);
+            })
+        }
+
+        self.parse_os().into_iter().map(to_str_or_abort).collect()
+    }
+
+    /// Initialize the value of all flags, accepting non-UTF8 positional
+    /// arguments, as by [`gflags::parse_os()`].
+    ///
+    /// [`gflags::parse_os()`]: crate::parse_os()
+    pub fn parse_os(&self) -> Vec<&'static OsStr> {
+        let mut command_line = CommandLine::new(self);
+        while let Some(token) = command_line.tokens.next() {
+            match token {
+                Token::Short(ch) => command_line.short(ch),
+                Token::Long(name) => command_line.long(name),
+                Token::LongEq(name, arg) => command_line.long_eq(name, arg),
+                Token::Arg(arg) => command_line.args.push(arg),
+            }
+        }
+        command_line.finish()
+    }
+
+    /// Print help for these options, as by [`gflags::print_help_and_exit()`].
+    ///
+    /// The help text reflects the options; for example bool flags are shown as
+    /// `--name` rather than `--[no-]name` if [`negation`] is disabled.
+    ///
+    /// [`gflags::print_help_and_exit()`]: crate::print_help_and_exit()
+    /// [`negation`]: ParseOptions::negation
+    pub fn print_help_and_exit(&self, code: i32) -> ! {
+        help::help_and_exit(code, false, self.negation);
+    }
+
+    /// Print help for these options including hidden flags, as by
+    /// [`gflags::print_full_help_and_exit()`].
+    ///
+    /// [`gflags::print_full_help_and_exit()`]: crate::print_full_help_and_exit()
+    pub fn print_full_help_and_exit(&self, code: i32) -> ! {
+        help::help_and_exit(code, true, self.negation);
+    }
+}
+
+impl ParseOptions {
+    // How the tokenizer splits up the command line under these options.
+    pub(crate) fn syntax(&self) -> Syntax {
+        Syntax {
+            stop_at_positional: self.stop_at_first_positional,
+            double_dash: self.double_dash,
+            keep_double_dash: self.pass_through_unknown,
+            single_dash_long: self.single_dash_long,
+            short_equals: self.short_equals,
+        }
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions::new()
+    }
+}
+
+// State of parsing one command line.
+struct CommandLine<'a> {
+    shorts: BTreeMap<char, &'static Flag>,
+    longs: BTreeMap<&'static str, &'static Flag>,
+    tokens: Tokenizer,
+    args: Vec<&'static OsStr>,
+    warned: BTreeSet<&'static str>,
+    passed: Vec<(Name, Range<usize>)>,
+    undefok: BTreeSet<&'a str>,
+    options: &'a ParseOptions,
+}
+
+impl<'a> CommandLine<'a> {
+    fn new(options: &'a ParseOptions) -> Self {
+        let mut shorts = BTreeMap::new();
+        let mut longs = BTreeMap::new();
+        for flag in inventory::iter::<Flag> {
+            for &short in flag.short.iter().chain(flag.short_aliases) {
+                if let Some(previous) = shorts.insert(short, flag) {
+                    duplicate_name(Name::short(short), previous, flag);
                 }
-            },
-            Token::Long(name) => match longs.get(name) {
-                Some(flag) => {
-                    let name = Name::long(flag.name);
-                    flag.parser.parse(name, &mut tokens);
+            }
+            for &name in Some(&flag.name).into_iter().chain(flag.aliases) {
+                if let Some(previous) = longs.insert(name, flag) {
+                    duplicate_name(Name::long(name), previous, flag);
                 }
-                None => {
-                    if name.starts_with("no") {
-                        if let Some(flag) = longs.get(&name[2..]) {
-                            if flag.parser.is_bool() {
-                                flag.parser.unset_bool();
-                                continue;
-                            }
-                        }
-                    }
-                    eprintln!("Unrecognized flag: --{}", name);
-                    process::exit(1);
+            }
+        }
+
+        check_forwarding(&longs);
+
+        CommandLine {
+            shorts,
+            longs,
+            tokens: Tokenizer::new().syntax(options.syntax()),
+            args: Vec::new(),
+            warned: BTreeSet::new(),
+            passed: Vec::new(),
+            undefok: options
+                .allow_unknown
+                .iter()
+                .map(String::as_str)
+                .chain(scan_undefok(options))
+                .collect(),
+            options,
+        }
+    }
+
+    fn origin(&self) -> Origin {
+        Origin::CommandLine {
+            index: self.tokens.index(),
+        }
+    }
+
+    fn check_deprecated(&mut self, flag: &'static Flag, name: Name) -> &'static Flag {
+        check_deprecated(flag, name, &self.longs, &mut self.warned)
+    }
+
+    fn short(&mut self, ch: char) {
+        match self.shorts.get(&ch) {
+            Some(&flag) => {
+                let name = Name::short(ch);
+                let flag = self.check_deprecated(flag, name);
+                if let Some(value) = self.tokens.attached_value() {
+                    flag.parser
+                        .parse_arg(name, OsStr::new(value), self.origin());
+                    return;
                 }
-            },
-            Token::LongEq(name, arg) => {
-                if let Some(flag) = longs.get(name) {
-                    if flag.parser.is_bool() {
-                        eprintln!("Unexpected argument {:?} for flag: --{}={0}", arg, name);
+                let count = if flag.parser.is_bool() {
+                    self.count(ch)
+                } else {
+                    1
+                };
+                if !self.options.cluster_shorts {
+                    let rest = self.tokens.rest_of_cluster();
+                    if !rest.is_empty() {
+                        eprintln!("Unexpected `{}` after -{}", rest, ch);
                         process::exit(1);
                     }
+                }
+                flag.parser.parse(name, &mut self.tokens);
+                flag.parser.add_repeats(count - 1);
+            }
+            None if self.options.pass_through_unknown => {
+                let rest = self.tokens.rest_of_cluster();
+                let value = if rest.is_empty() {
+                    self.tokens.next_value()
+                } else {
+                    None
+                };
+                self.pass_through(Name::short(ch), format!("-{}{}", ch, rest), value);
+            }
+            None if self.ignored(Name::short(ch)) => {
+                // Like pass-through, treat the rest of the cluster as the
+                // ignored flag's value, or else the next arg if it is not
+                // a flag.
+                if self.tokens.rest_of_cluster().is_empty() {
+                    self.tokens.next_value();
+                }
+            }
+            None => unrecognized(Name::short(ch)),
+        }
+    }
 
-                    let name = Name::long(flag.name);
+    // Number of times a bool short flag counts as repeated, which is given by
+    // attached digits as in `-v3`.
+    fn count(&mut self, ch: char) -> usize {
+        let digits = self.tokens.attached_digits();
+        if digits.is_empty() {
+            return 1;
+        }
+        match digits.parse::<u32>() {
+            Ok(count) if count > 0 => count as usize,
+            _ => {
+                eprintln!("Invalid count `{}` for -{}", digits, ch);
+                process::exit(1);
+            }
+        }
+    }
 
-                    // Prepare an iterator and tokenizer for just this arg, then
-                    // parse the arg for the flag
-                    let arg = vec![OsStr::new(arg)];
-                    let mut tokens = Tokenizer::iterate(arg);
+    fn long(&mut self, name: &'static str) {
+        if let Some((name, flag)) = self.lookup_long(name) {
+            let name = Name::long(name);
+            let flag = self.check_deprecated(flag, name);
+            flag.parser.parse(name, &mut self.tokens);
+            return;
+        }
 
-                    flag.parser.parse(name, &mut tokens);
-                } else {
-                    eprintln!("Unrecognized flag: --{}", name);
+        if name == UNDEFOK {
+            let list = match self.tokens.next_arg().map(OsStr::to_str) {
+                Some(Some(list)) => list,
+                Some(None) => {
+                    eprintln!("Non-unicode arg for --{}", UNDEFOK);
+                    process::exit(1);
+                }
+                None => {
+                    eprintln!("Missing value for `--{}`", UNDEFOK);
                     process::exit(1);
                 }
+            };
+            self.undefok.extend(list.split(','));
+            return;
+        }
+
+        if self.options.negation {
+            if let Some(&flag) = strip_negation(name).and_then(|base| self.longs.get(base)) {
+                if flag.parser.is_bool() && flag.negatable {
+                    let flag = self.check_deprecated(flag, Name::long(name));
+                    flag.parser.unset_bool(self.origin());
+                    return;
+                }
+            }
+        }
+
+        if self.options.pass_through_unknown {
+            let value = self.tokens.next_value();
+            self.pass_through(Name::long(name), format!("--{}", name), value);
+        } else if self.ignored(Name::long(name)) {
+            // The flag's type is unknown, so a following arg that is not a
+            // flag is assumed to be its value.
+            self.tokens.next_value();
+        } else {
+            unrecognized(Name::long(name));
+        }
+    }
+
+    fn long_eq(&mut self, name: &'static str, arg: &'static str) {
+        if let Some((name, flag)) = self.lookup_long(name) {
+            let name = Name::long(name);
+            let flag = self.check_deprecated(flag, name);
+            flag.parser.parse_arg(name, OsStr::new(arg), self.origin());
+        } else if name == UNDEFOK {
+            self.undefok.extend(arg.split(','));
+        } else if self.options.pass_through_unknown {
+            self.pass_through(Name::long(name), format!("--{}={}", name, arg), None);
+        } else if !self.ignored(Name::long(name)) {
+            unrecognized(Name::long(name));
+        }
+    }
+
+    // Finds the flag with the given long name, or whose name it abbreviates if
+    // abbreviations are enabled. Returns the full name along with the flag.
+    fn lookup_long(&self, name: &'static str) -> Option<(&'static str, &'static Flag)> {
+        if let Some(&flag) = self.longs.get(name) {
+            return Some((name, flag));
+        }
+        if !self.options.abbreviations || name.is_empty() {
+            return None;
+        }
+
+        let mut candidates = self
+            .longs
+            .range::<str, _>((Bound::Included(name), Bound::Unbounded))
+            .take_while(|(long, _flag)| long.starts_with(name));
+        let (&first_name, &first) = candidates.next()?;
+        let mut flags = vec![first];
+        for (_long, &flag) in candidates {
+            if !flags.iter().any(|&seen| ptr::eq(seen, flag)) {
+                flags.push(flag);
+            }
+        }
+        if flags.len() == 1 {
+            return Some((first_name, first));
+        }
+
+        let mut message = format!("Ambiguous flag: --{} could be ", name);
+        for (i, flag) in flags.iter().enumerate() {
+            if i > 0 {
+                message.push_str(", ");
+            }
+            message.push_str("--");
+            message.push_str(flag.name);
+        }
+        eprintln!("{}", message);
+        process::exit(1);
+    }
+
+    fn pass_through(&mut self, name: Name, raw: String, value: Option<&'static OsStr>) {
+        let start = self.args.len();
+        let raw = Box::leak(raw.into_boxed_str());
+        self.args.push(OsStr::new(raw));
+        self.args.extend(value);
+        self.passed.push((name, start..self.args.len()));
+    }
+
+    // Whether --undefok or allow_unknown covers an unknown flag.
+    fn ignored(&self, name: Name) -> bool {
+        let bare = name.bare();
+        let negated = strip_negation(&bare).unwrap_or(&bare);
+        self.undefok.contains(bare.as_str()) || self.undefok.contains(negated)
+    }
+
+    fn finish(self) -> Vec<&'static OsStr> {
+        let mut args = Vec::with_capacity(self.args.len());
+        let mut next = 0;
+        for (name, range) in &self.passed {
+            if self.ignored(*name) {
+                args.extend_from_slice(&self.args[next..range.start]);
+                next = range.end;
+            }
+        }
+        args.extend_from_slice(&self.args[next..]);
+
+        let mut errors = constraint::check();
+        if errors.is_empty() {
+            errors = hook::run();
+        }
+        if !errors.is_empty() {
+            for error in errors {
+                eprintln!("{}", error);
+            }
+            process::exit(1);
+        }
+
+        state::finish_parsing();
+        args
+    }
+}
+
+fn unrecognized(name: Name) -> ! {
+    eprintln!("Unrecognized flag: {}", name);
+    process::exit(1);
+}
+
+// The names listed by every --undefok on the command line. These are collected
+// before parsing so that --undefok applies to flags that come before it too.
+fn scan_undefok(options: &ParseOptions) -> Vec<&'static str> {
+    let mut names = Vec::new();
+    let mut args = argv::iter().skip(1).map(OsStr::to_str);
+    while let Some(arg) = args.next() {
+        let arg = match arg {
+            Some(arg) => arg,
+            None => continue,
+        };
+        if arg == "--" && options.double_dash {
+            break;
+        }
+        let flag = if arg.starts_with("--") {
+            &arg[2..]
+        } else if arg.starts_with('-') && options.single_dash_long {
+            &arg[1..]
+        } else {
+            continue;
+        };
+        let list = if flag == UNDEFOK {
+            match args.next() {
+                Some(Some(list)) => list,
+                Some(None) => continue,
+                None => break,
             }
-            Token::Arg(arg) => args.push(arg),
+        } else if flag.starts_with(UNDEFOK) && flag[UNDEFOK.len()..].starts_with('=') {
+            &flag[UNDEFOK.len() + 1..]
+        } else {
+            continue;
+        };
+        names.extend(list.split(','));
+    }
+    names
+}
+
+// The name of the flag negated by `--noname` or `--no-name`.
+fn strip_negation(name: &str) -> Option<&str> {
+    if name.starts_with("no-") {
+        Some(&name[3..])
+    } else if name.starts_with("no") {
+        Some(&name[2..])
+    } else {
+        None
+    }
+}
+
+// Two flags, or a flag and an alias of another, claiming the same name would
+// otherwise resolve silently to whichever was registered last.
+fn duplicate_name(name: Name, previous: &Flag, flag: &Flag) -> ! {
+    panic!(
+        "flag {} is defined more than once, by --{} (defined at {}:{}) and --{} (defined at {}:{})",
+        name, previous.name, previous.file, previous.line, flag.name, flag.file, flag.line,
+    );
+}
+
+// A deprecated flag is parsed as if it were its replacement, so the replacement
+// must exist and take the same kind of value. Checked up front so that a bad
+// forward_to fails every run rather than only runs which use the old flag.
+pub(crate) fn check_forwarding(longs: &BTreeMap<&'static str, &'static Flag>) {
+    for flag in longs.values() {
+        let forward_to = match flag.deprecated.as_ref().and_then(|d| d.forward_to) {
+            Some(forward_to) => forward_to,
+            None => continue,
+        };
+        let replacement = match longs.get(forward_to) {
+            Some(replacement) => replacement,
+            None => panic!(
+                "deprecated flag --{} (defined at {}:{}) is forwarded to --{}, which is not defined",
+                flag.name, flag.file, flag.line, forward_to,
+            ),
+        };
+        assert!(
+            flag.parser.is_bool() == replacement.parser.is_bool(),
+            "deprecated flag --{} (defined at {}:{}) is forwarded to --{}, but only one of them is a bool flag",
+            flag.name, flag.file, flag.line, forward_to,
+        );
+    }
+}
+
+// Warns the first time a deprecated flag is used, and returns the flag that
+// should receive the value, which is different if the deprecated flag has been
+// forwarded to a replacement.
+pub(crate) fn check_deprecated(
+    flag: &'static Flag,
+    name: Name,
+    longs: &BTreeMap<&str, &'static Flag>,
+    warned: &mut BTreeSet<&'static str>,
+) -> &'static Flag {
+    let deprecated = match &flag.deprecated {
+        Some(deprecated) => deprecated,
+        None => return flag,
+    };
+
+    if warned.insert(flag.name) {
+        match deprecated.note {
+            Some(note) => warn::warn(&format!("flag {} is deprecated: {}", name, note)),
+            None => warn::warn(&format!("flag {} is deprecated", name)),
         }
     }
 
-    args
+    match deprecated.forward_to {
+        Some(forward_to) => longs[forward_to],
+        None => flag,
+    }
 }
@@ -1,13 +1,52 @@
 use crate::dispatch::Parser;
+use crate::error::Result;
 
 // Not public API.
 #[doc(hidden)]
 pub struct Flag {
     pub doc: &'static [&'static str],
     pub short: Option<char>,
+    pub short_aliases: &'static [char],
     pub name: &'static str,
+    pub aliases: &'static [&'static str],
     pub placeholder: Option<&'static str>,
+    pub deprecated: Option<Deprecated>,
+    pub hidden: bool,
+    pub negatable: bool,
+    pub file: &'static str,
+    pub line: u32,
     pub parser: &'static dyn Parser,
 }
 
+// Not public API.
+#[doc(hidden)]
+pub struct Deprecated {
+    pub note: Option<&'static str>,
+    pub forward_to: Option<&'static str>,
+}
+
+// Not public API.
+#[doc(hidden)]
+pub struct Constraint {
+    pub kind: ConstraintKind,
+    pub flag: &'static dyn Parser,
+    pub others: &'static [&'static dyn Parser],
+}
+
+// Not public API.
+#[doc(hidden)]
+pub enum ConstraintKind {
+    Requires,
+    ConflictsWith,
+}
+
+// Not public API.
+#[doc(hidden)]
+pub struct Hook {
+    pub run: fn() -> Result<()>,
+    pub order: i32,
+}
+
 inventory::collect!(Flag);
+inventory::collect!(Constraint);
+inventory::collect!(Hook);
@@ -1,7 +1,22 @@
 use crate::atomic::StaticAtomicPtr;
+use crate::callback::Callbacks;
+use crate::origin::{Origin, OriginCell};
 use ref_cast::RefCast;
 use std::ops::Deref;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+// Whether gflags::parse has finished assigning the command line flags. Changes
+// to flag values after this point are reported to on_change callbacks.
+static PARSED: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn finish_parsing() {
+    PARSED.store(true, Ordering::Release);
+}
+
+#[cfg(feature = "config")]
+pub(crate) fn parsing_finished() -> bool {
+    PARSED.load(Ordering::Acquire)
+}
 
 /// The state associated with a single flag.
 ///
@@ -38,19 +53,25 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 /// ```
 pub struct Flag<T> {
     atomic: StaticAtomicPtr<T>,
+    default: StaticAtomicPtr<T>,
     present: AtomicUsize,
+    origin: OriginCell,
+    callbacks: Callbacks<T>,
 }
 
 impl<T: 'static> Flag<T> {
-    /// Whether this flag was provided on the command line.
+    /// Whether this flag has been assigned, whether on the command line, by a
+    /// config file or environment variable, or at runtime.
     ///
     /// When using flags for which a default value is not provided, be sure to
     /// check `.is_present()` because accessing `.flag` when not present will
     /// cause a panic.
     ///
-    /// When a flag has a default value and is not passed on the command line,
-    /// `is_present()` will be false and `.flag` will refer to the default
-    /// value.
+    /// When a flag has a default value and is never assigned, `is_present()`
+    /// will be false and `.flag` will refer to the default value. See
+    /// [`origin()`] for where an assigned value came from.
+    ///
+    /// [`origin()`]: Flag::origin
     pub fn is_present(&self) -> bool {
         self.present.load(Ordering::Acquire) != 0
     }
@@ -61,7 +82,64 @@ impl<T: 'static> Flag<T> {
     /// several times. For example, `-vv` for "very verbose" (repeat count 2)
     /// or `-ddd` for debug level 3.
     pub fn repeat_count(&self) -> u32 {
-        self.present.load(Ordering::Acquire) as u32
+        let present = self.present.load(Ordering::Acquire);
+        present.min(u32::MAX as usize) as u32
+    }
+
+    /// Where the current value of this flag came from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gflags::Origin;
+    ///
+    /// gflags::define! {
+    ///     --max_qps: u32 = 100
+    /// }
+    ///
+    /// fn main() {
+    ///     gflags::parse();
+    ///
+    ///     if let Origin::CommandLine { index } = MAX_QPS.origin() {
+    ///         println!("--max_qps was set by argument {}", index);
+    ///     }
+    /// }
+    /// ```
+    pub fn origin(&self) -> Origin {
+        self.origin.get()
+    }
+
+    /// Register a callback to run whenever the value of this flag changes
+    /// after [`gflags::parse()`] has finished.
+    ///
+    /// Values assigned from the command line during `gflags::parse()` do not
+    /// trigger callbacks. Later changes do, such as a flag being set at runtime
+    /// in a running process. The callback receives the new value and runs on
+    /// the thread that made the change, without any lock held, so it is free
+    /// to read this or any other flag.
+    ///
+    /// [`gflags::parse()`]: fn.parse.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// gflags::define! {
+    ///     --max_qps: u32 = 100
+    /// }
+    ///
+    /// fn main() {
+    ///     gflags::parse();
+    ///
+    ///     MAX_QPS.on_change(|max_qps| {
+    ///         println!("rate limit changed to {} queries per second", max_qps);
+    ///     });
+    /// }
+    /// ```
+    pub fn on_change<F>(&self, callback: F)
+    where
+        F: Fn(&T) + Send + Sync + 'static,
+    {
+        self.callbacks.push(Box::new(callback));
     }
 }
 
@@ -78,7 +156,10 @@ impl<T: 'static> Flag<T> {
     pub const fn new(default: &'static T) -> Self {
         Flag {
             atomic: StaticAtomicPtr::new(default),
+            default: StaticAtomicPtr::new(default),
             present: AtomicUsize::new(0),
+            origin: OriginCell::new(),
+            callbacks: Callbacks::new(),
         }
     }
 
@@ -87,22 +168,90 @@ impl<T: 'static> Flag<T> {
     pub const fn null() -> Self {
         Flag {
             atomic: StaticAtomicPtr::null(),
+            default: StaticAtomicPtr::null(),
             present: AtomicUsize::new(0),
+            origin: OriginCell::new(),
+            callbacks: Callbacks::new(),
         }
     }
 
+    pub(crate) fn current_value(&self) -> Option<&'static T> {
+        self.atomic.try_load(Ordering::Acquire)
+    }
+
+    pub(crate) fn default_value(&self) -> Option<&'static T> {
+        self.default.try_load(Ordering::Relaxed)
+    }
+
+    // Records the origin of the value about to be assigned by the next call to
+    // set or set_bool.
+    pub(crate) fn set_origin(&self, origin: Origin) {
+        self.origin.set(origin);
+    }
+
     pub(crate) fn set(&self, value: T) {
         let ptr = Box::leak(Box::new(value));
-        self.atomic.store(ptr, Ordering::Release);
+        self.store(ptr);
+    }
+
+    // Counts a flag given as `-v3` as repeated beyond the one assignment,
+    // saturating at `u32::MAX` like `repeat_count`.
+    pub(crate) fn add_repeats(&self, extra: usize) {
+        let _ = self
+            .present
+            .fetch_update(Ordering::Release, Ordering::Acquire, |present| {
+                Some(present.saturating_add(extra).min(u32::MAX as usize))
+            });
+    }
+
+    fn store(&self, value: &'static T) {
+        self.assign_quietly(value);
+        if PARSED.load(Ordering::Acquire) {
+            self.callbacks.call(value);
+        }
+    }
+
+    // Assigns a value without running on_change callbacks, so that it can still
+    // be undone by restore. The caller runs the callbacks later by notify.
+    pub(crate) fn assign_quietly(&self, value: &'static T) {
+        self.atomic.store(value, Ordering::Release);
         self.present.fetch_add(1, Ordering::Release);
     }
+
+    pub(crate) fn notify(&self) {
+        if PARSED.load(Ordering::Acquire) {
+            if let Some(value) = self.current_value() {
+                self.callbacks.call(value);
+            }
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> Snapshot<T> {
+        Snapshot {
+            value: self.current_value(),
+            present: self.present.load(Ordering::Acquire),
+            origin: self.origin(),
+        }
+    }
+
+    pub(crate) fn restore(&self, snapshot: Snapshot<T>) {
+        self.atomic.store_option(snapshot.value, Ordering::Release);
+        self.present.store(snapshot.present, Ordering::Release);
+        self.origin.set(snapshot.origin);
+    }
+}
+
+// The state of a flag before an assignment, for undoing the assignment.
+pub(crate) struct Snapshot<T: 'static> {
+    value: Option<&'static T>,
+    present: usize,
+    origin: Origin,
 }
 
 impl Flag<bool> {
     #[allow(clippy::trivially_copy_pass_by_ref)]
     pub(crate) fn set_bool(&self, value: &'static bool) {
-        self.atomic.store(value, Ordering::Release);
-        self.present.fetch_add(1, Ordering::Release);
+        self.store(value);
     }
 }
 
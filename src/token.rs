@@ -1,10 +1,32 @@
+use crate::parse::ParseOptions;
+use crate::value;
 use std::ffi::OsStr;
+use std::iter::Peekable;
 
 pub struct Tokenizer {
-    args: Box<dyn Iterator<Item = &'static OsStr>>,
+    args: Peekable<Box<dyn Iterator<Item = &'static OsStr>>>,
     pending: &'static str,
     pending_index: usize,
     rest_are_args: bool,
+    consumed: usize,
+    syntax: Syntax,
+}
+
+/// Command line conventions that vary between programs.
+#[derive(Copy, Clone)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Syntax {
+    /// Treat everything after the first positional argument as positional.
+    pub stop_at_positional: bool,
+    /// Treat everything after `--` as positional.
+    pub double_dash: bool,
+    /// Return the `--` itself as positional too.
+    pub keep_double_dash: bool,
+    /// Treat `-name` and `-name=value` as long flags, leaving only a dash
+    /// followed by a single character as a short flag.
+    pub single_dash_long: bool,
+    /// Accept a value attached to a short flag with `=`, as in `-f=value`.
+    pub short_equals: bool,
 }
 
 #[derive(Debug, PartialEq)]
@@ -24,7 +46,9 @@ impl Tokenizer {
         // Skip the executable.
         let _ = args.next();
 
-        Tokenizer::iterate(args)
+        let mut tokenizer = Tokenizer::iterate(args);
+        tokenizer.consumed = 1;
+        tokenizer
     }
 
     /// Creates a `Tokenizer` that iterates over the `args`, processing all items.
@@ -33,14 +57,35 @@ impl Tokenizer {
         I: IntoIterator<Item = &'static S> + 'static,
         S: AsRef<OsStr> + ?Sized + 'static,
     {
+        let args: Box<dyn Iterator<Item = &'static OsStr>> =
+            Box::new(args.into_iter().map(AsRef::as_ref));
         Tokenizer {
-            args: Box::new(args.into_iter().map(AsRef::as_ref)),
+            args: args.peekable(),
             pending: "",
             pending_index: 0,
             rest_are_args: false,
+            consumed: 0,
+            syntax: ParseOptions::new().syntax(),
         }
     }
 
+    pub(crate) fn syntax(mut self, syntax: Syntax) -> Self {
+        self.syntax = syntax;
+        self
+    }
+
+    /// Position of the most recently consumed argument, counting the
+    /// executable as position 0 when iterating over the real command line.
+    pub fn index(&self) -> usize {
+        self.consumed.saturating_sub(1)
+    }
+
+    fn next_raw(&mut self) -> Option<&'static OsStr> {
+        let arg = self.args.next()?;
+        self.consumed += 1;
+        Some(arg)
+    }
+
     pub fn next(&mut self) -> Option<Token> {
         if self.pending_index < self.pending.len() {
             let ch = self.pending[self.pending_index..].chars().next().unwrap();
@@ -48,42 +93,100 @@ impl Tokenizer {
             return Some(Token::Short(ch));
         }
 
-        let arg = self.args.next()?;
+        let arg = self.next_raw()?;
         if self.rest_are_args {
             return Some(Token::Arg(arg));
         }
 
         let string = match arg.to_str() {
             Some(string) => string,
-            None => return Some(Token::Arg(arg)),
+            None => return Some(self.positional(arg)),
         };
 
-        if string == "--" {
+        if string == "--" && self.syntax.double_dash {
             self.rest_are_args = true;
-            return self.args.next().map(Token::Arg);
+            if self.syntax.keep_double_dash {
+                return Some(Token::Arg(arg));
+            }
+            return self.next_raw().map(Token::Arg);
+        }
+
+        if string.starts_with("--") && string != "--" {
+            return Some(long(&string[2..]));
         }
 
-        if string.starts_with("--") {
-            return match string.find('=') {
-                // "--foo bar" case
-                None => Some(Token::Long(&string[2..])),
-                // "--foo=bar" case
-                Some(i) => {
-                    let flag = &string[2..i];
-                    let arg = &string[i + 1..];
-                    Some(Token::LongEq(flag, arg))
-                }
-            };
+        let is_flag = string.starts_with('-')
+            && string != "-"
+            && string != "--"
+            && !is_negative_number(string);
+
+        // With `short_equals`, `-f=value` is a short flag even if single-dash
+        // long flags are enabled.
+        let second = string[1..].chars().nth(1);
+        let short_eq = self.syntax.short_equals && second == Some('=');
+        if is_flag && self.syntax.single_dash_long && second.is_some() && !short_eq {
+            return Some(long(&string[1..]));
         }
 
-        if string.starts_with('-') && string != "-" {
+        if is_flag {
             let ch = string[1..].chars().next().unwrap();
             self.pending = string;
             self.pending_index = 1 + ch.len_utf8();
             return Some(Token::Short(ch));
         }
 
-        Some(Token::Arg(OsStr::new(string)))
+        Some(self.positional(OsStr::new(string)))
+    }
+
+    fn positional(&mut self, arg: &'static OsStr) -> Token {
+        if self.syntax.stop_at_positional {
+            self.rest_are_args = true;
+        }
+        Token::Arg(arg)
+    }
+
+    /// Consumes the remaining characters of the current cluster of short
+    /// flags, which is empty if the last short flag was the end of its arg.
+    pub fn rest_of_cluster(&mut self) -> &'static str {
+        let rest = &self.pending[self.pending_index..];
+        self.pending_index = self.pending.len();
+        rest
+    }
+
+    /// Consumes a value attached to the most recent short flag with `=`, as in
+    /// `-f=value`, if `short_equals` is enabled.
+    pub fn attached_value(&mut self) -> Option<&'static str> {
+        let rest = &self.pending[self.pending_index..];
+        if self.syntax.short_equals && rest.starts_with('=') {
+            self.pending_index = self.pending.len();
+            Some(&rest[1..])
+        } else {
+            None
+        }
+    }
+
+    /// Consumes the digits immediately following the most recent short flag,
+    /// as in `-v3`, which are empty if the flag is not followed by a digit.
+    pub fn attached_digits(&mut self) -> &'static str {
+        let rest = &self.pending[self.pending_index..];
+        let len = rest.bytes().take_while(u8::is_ascii_digit).count();
+        self.pending_index += len;
+        &rest[..len]
+    }
+
+    /// Consumes the next arg only if it does not look like a flag, for use as
+    /// the value of an unknown flag which may or may not take one.
+    pub fn next_value(&mut self) -> Option<&'static OsStr> {
+        let next = *self.args.peek()?;
+        let is_flag = match next.to_str() {
+            Some(string) => string.starts_with('-') && string != "-" && !is_negative_number(string),
+            None => false,
+        };
+        if is_flag || self.rest_are_args {
+            None
+        } else {
+            self.next_raw()
+        }
     }
 
     pub fn next_arg(&mut self) -> Option<&'static OsStr> {
@@ -92,15 +195,32 @@ impl Tokenizer {
             self.pending_index = self.pending.len();
             Some(OsStr::new(rest))
         } else {
-            self.args.next()
+            self.next_raw()
         }
     }
 }
 
+fn long(string: &'static str) -> Token {
+    match string.find('=') {
+        // "--foo bar" case
+        None => Token::Long(string),
+        // "--foo=bar" case
+        Some(i) => Token::LongEq(&string[..i], &string[i + 1..]),
+    }
+}
+
+fn is_negative_number(string: &str) -> bool {
+    let numeric = matches!(
+        string.as_bytes(),
+        [b'-', b'0'..=b'9', ..] | [b'-', b'.', b'0'..=b'9', ..]
+    );
+    numeric && (string.parse::<f64>().is_ok() || value::parse_int::<i128>(string).is_ok())
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Token;
-    use super::Tokenizer;
+    use super::{Syntax, Token, Tokenizer};
+    use crate::parse::ParseOptions;
     use std::ffi::OsStr;
 
     /// `-a` should work.
@@ -204,4 +324,190 @@ mod tests {
         assert_eq!(tokenizer.next().unwrap(), Token::Arg(OsStr::new("-b")));
         assert_eq!(tokenizer.next().unwrap(), Token::Arg(OsStr::new("hello")));
     }
+
+    /// The index refers to the argument containing the most recent token.
+    #[test]
+    fn index() {
+        let args = &["-ab", "--c", "d", "--", "e"];
+        let mut tokenizer = Tokenizer::iterate(args);
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('a'));
+        assert_eq!(tokenizer.index(), 0);
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('b'));
+        assert_eq!(tokenizer.index(), 0);
+        assert_eq!(tokenizer.next().unwrap(), Token::Long("c"));
+        assert_eq!(tokenizer.index(), 1);
+        assert_eq!(tokenizer.next_arg().unwrap(), OsStr::new("d"));
+        assert_eq!(tokenizer.next().unwrap(), Token::Arg(OsStr::new("e")));
+        assert_eq!(tokenizer.index(), 4);
+    }
+
+    /// The rest of a cluster can be taken without splitting it into flags.
+    #[test]
+    fn rest_of_cluster() {
+        let args = &["-abc", "-d"];
+        let mut tokenizer = Tokenizer::iterate(args);
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('a'));
+        assert_eq!(tokenizer.rest_of_cluster(), "bc");
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('d'));
+        assert_eq!(tokenizer.rest_of_cluster(), "");
+        assert_eq!(tokenizer.next(), None);
+    }
+
+    /// With `stop_at_positional`, flags after the first positional are args.
+    #[test]
+    fn stop_at_positional() {
+        let args = &["-a", "--b", "c", "file", "-d", "--e"];
+        let syntax = Syntax {
+            stop_at_positional: true,
+            ..ParseOptions::new().syntax()
+        };
+        let mut tokenizer = Tokenizer::iterate(args).syntax(syntax);
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('a'));
+        assert_eq!(tokenizer.next().unwrap(), Token::Long("b"));
+        assert_eq!(tokenizer.next_arg().unwrap(), "c");
+        assert_eq!(tokenizer.next().unwrap(), Token::Arg(OsStr::new("file")));
+        assert_eq!(tokenizer.next().unwrap(), Token::Arg(OsStr::new("-d")));
+        assert_eq!(tokenizer.next().unwrap(), Token::Arg(OsStr::new("--e")));
+    }
+
+    /// Without `double_dash`, "--" is an ordinary arg.
+    #[test]
+    fn double_dash_disabled() {
+        let args = &["--", "-a"];
+        let syntax = Syntax {
+            double_dash: false,
+            ..ParseOptions::new().syntax()
+        };
+        let mut tokenizer = Tokenizer::iterate(args).syntax(syntax);
+        assert_eq!(tokenizer.next().unwrap(), Token::Arg(OsStr::new("--")));
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('a'));
+    }
+
+    /// With `single_dash_long`, `-name` is a long flag but `-a` is still short.
+    #[test]
+    fn single_dash_long() {
+        let args = &["-verbose", "-level=3", "-a", "--b"];
+        let syntax = Syntax {
+            single_dash_long: true,
+            ..ParseOptions::new().syntax()
+        };
+        let mut tokenizer = Tokenizer::iterate(args).syntax(syntax);
+        assert_eq!(tokenizer.next().unwrap(), Token::Long("verbose"));
+        assert_eq!(tokenizer.next().unwrap(), Token::LongEq("level", "3"));
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('a'));
+        assert_eq!(tokenizer.next().unwrap(), Token::Long("b"));
+    }
+
+    /// Negative numbers are args, whether positional or the value of a flag.
+    #[test]
+    fn negative_numbers() {
+        let args = &["-5", "--offset", "-1.5e3", "-0x1f", "-.5", "-5x", "-e5"];
+        let mut tokenizer = Tokenizer::iterate(args);
+        assert_eq!(tokenizer.next().unwrap(), Token::Arg(OsStr::new("-5")));
+        assert_eq!(tokenizer.next().unwrap(), Token::Long("offset"));
+        assert_eq!(tokenizer.next_arg().unwrap(), "-1.5e3");
+        assert_eq!(tokenizer.next().unwrap(), Token::Arg(OsStr::new("-0x1f")));
+        assert_eq!(tokenizer.next().unwrap(), Token::Arg(OsStr::new("-.5")));
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('5'));
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('x'));
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('e'));
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('5'));
+    }
+
+    /// `-f=value` attaches a value to a short flag.
+    #[test]
+    fn short_flag_eq() {
+        let args = &["-f=value", "-a", "-b="];
+        let syntax = Syntax {
+            short_equals: true,
+            ..ParseOptions::new().syntax()
+        };
+        let mut tokenizer = Tokenizer::iterate(args).syntax(syntax);
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('f'));
+        assert_eq!(tokenizer.attached_value(), Some("value"));
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('a'));
+        assert_eq!(tokenizer.attached_value(), None);
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('b'));
+        assert_eq!(tokenizer.attached_value(), Some(""));
+        assert_eq!(tokenizer.next(), None);
+    }
+
+    /// Without `short_equals`, `=` after a short flag is part of its value.
+    #[test]
+    fn short_flag_eq_disabled() {
+        let args = &["-f=value"];
+        let mut tokenizer = Tokenizer::iterate(args);
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('f'));
+        assert_eq!(tokenizer.attached_value(), None);
+        assert_eq!(tokenizer.next_arg().unwrap(), "=value");
+    }
+
+    /// With `single_dash_long`, `-f=value` is still a short flag if
+    /// `short_equals` is enabled, and a long flag named `f` otherwise.
+    #[test]
+    fn short_flag_eq_single_dash_long() {
+        let args = &["-f=value", "-level=3"];
+        let syntax = Syntax {
+            single_dash_long: true,
+            short_equals: true,
+            ..ParseOptions::new().syntax()
+        };
+        let mut tokenizer = Tokenizer::iterate(args).syntax(syntax);
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('f'));
+        assert_eq!(tokenizer.attached_value(), Some("value"));
+        assert_eq!(tokenizer.next().unwrap(), Token::LongEq("level", "3"));
+
+        let syntax = Syntax {
+            short_equals: false,
+            ..syntax
+        };
+        let mut tokenizer = Tokenizer::iterate(args).syntax(syntax);
+        assert_eq!(tokenizer.next().unwrap(), Token::LongEq("f", "value"));
+    }
+
+    /// `-v3q` is a short flag with a count, followed by another short flag.
+    #[test]
+    fn short_flag_digits() {
+        let args = &["-v3q", "-v"];
+        let mut tokenizer = Tokenizer::iterate(args);
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('v'));
+        assert_eq!(tokenizer.attached_digits(), "3");
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('q'));
+        assert_eq!(tokenizer.next().unwrap(), Token::Short('v'));
+        assert_eq!(tokenizer.attached_digits(), "");
+        assert_eq!(tokenizer.next(), None);
+    }
+
+    /// Only an arg that is not a flag is taken as an unknown flag's value.
+    #[test]
+    fn next_value() {
+        let args = &["--a", "x", "--b", "--c", "-5", "--d", "--", "y"];
+        let mut tokenizer = Tokenizer::iterate(args);
+        assert_eq!(tokenizer.next().unwrap(), Token::Long("a"));
+        assert_eq!(tokenizer.next_value().unwrap(), "x");
+        assert_eq!(tokenizer.next().unwrap(), Token::Long("b"));
+        assert_eq!(tokenizer.next_value(), None);
+        assert_eq!(tokenizer.next().unwrap(), Token::Long("c"));
+        assert_eq!(tokenizer.next_value().unwrap(), "-5");
+        assert_eq!(tokenizer.next().unwrap(), Token::Long("d"));
+        assert_eq!(tokenizer.next_value(), None);
+        assert_eq!(tokenizer.next().unwrap(), Token::Arg(OsStr::new("y")));
+    }
+
+    /// A non-UTF-8 positional also stops flag parsing.
+    #[cfg(unix)]
+    #[test]
+    fn stop_at_non_utf8_positional() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let args: &'static [&'static OsStr] =
+            Box::leak(Box::new([OsStr::from_bytes(b"\xff"), OsStr::new("-a")]));
+        let syntax = Syntax {
+            stop_at_positional: true,
+            ..ParseOptions::new().syntax()
+        };
+        let mut tokenizer = Tokenizer::iterate(args).syntax(syntax);
+        assert_eq!(tokenizer.next().unwrap(), Token::Arg(args[0]));
+        assert_eq!(tokenizer.next().unwrap(), Token::Arg(OsStr::new("-a")));
+    }
 }
@@ -0,0 +1,30 @@
+//! Ready-made flag data types.
+//!
+//! These types implement [`Value`] and may be used as the type of a flag in
+//! [`gflags::define!`] just like the built-in primitive types.
+//!
+//! [`Value`]: crate::custom::Value
+//! [`gflags::define!`]: crate::define
+//!
+//! # Examples
+//!
+//! ```
+//! use gflags::types::ByteSize;
+//!
+//! gflags::define! {
+//!     /// Maximum size of the in-memory block cache.
+//!     --cache_size <SIZE>: ByteSize = ByteSize::mib(64)
+//! }
+//! #
+//! # fn main() {}
+//! ```
+
+mod byte_size;
+mod list;
+mod map;
+mod secret;
+
+pub use self::byte_size::ByteSize;
+pub use self::list::List;
+pub use self::map::Map;
+pub use self::secret::Secret;
@@ -0,0 +1,252 @@
+use crate::custom::{Arg, Error, Result, Value};
+use std::fmt::{self, Display};
+
+/// A number of bytes, written with an optional SI or IEC unit suffix.
+///
+/// Accepted forms include plain integers like `4096`, and numbers with a unit
+/// like `64KiB`, `1.5GB` or `512M`. Units are case-insensitive.
+///
+/// | Unit | Meaning |
+/// |---|---|
+/// | `B` | 1 |
+/// | `KB`, `MB`, `GB`, `TB`, `PB`, `EB` | powers of 1000 |
+/// | `KiB`, `MiB`, `GiB`, `TiB`, `PiB`, `EiB` | powers of 1024 |
+/// | `K`, `M`, `G`, `T`, `P`, `E` | powers of 1024 |
+///
+/// Fractional values are accepted as long as they amount to a whole number of
+/// bytes. In help text the value is rendered with the largest unit that
+/// represents it exactly, such as `64MiB` or `1.5GB`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteSize(pub u64);
+
+const KIB: u64 = 1 << 10;
+const MIB: u64 = 1 << 20;
+const GIB: u64 = 1 << 30;
+
+const UNITS: &[(&str, u64)] = &[
+    ("EiB", 1 << 60),
+    ("EB", 1_000_000_000_000_000_000),
+    ("PiB", 1 << 50),
+    ("PB", 1_000_000_000_000_000),
+    ("TiB", 1 << 40),
+    ("TB", 1_000_000_000_000),
+    ("GiB", GIB),
+    ("GB", 1_000_000_000),
+    ("MiB", MIB),
+    ("MB", 1_000_000),
+    ("KiB", KIB),
+    ("KB", 1_000),
+    ("B", 1),
+];
+
+impl ByteSize {
+    pub const fn b(bytes: u64) -> Self {
+        ByteSize(bytes)
+    }
+
+    pub const fn kib(kib: u64) -> Self {
+        ByteSize(kib * KIB)
+    }
+
+    pub const fn mib(mib: u64) -> Self {
+        ByteSize(mib * MIB)
+    }
+
+    pub const fn gib(gib: u64) -> Self {
+        ByteSize(gib * GIB)
+    }
+
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(size: ByteSize) -> Self {
+        size.0
+    }
+}
+
+impl Value for ByteSize {
+    fn parse(arg: Arg) -> Result<Self> {
+        parse_byte_size(arg.get_str())
+    }
+
+    fn render(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+fn parse_byte_size(string: &str) -> Result<ByteSize> {
+    let number_len = string
+        .find(|ch: char| !ch.is_ascii_digit() && ch != '.')
+        .unwrap_or(string.len());
+    let number = &string[..number_len];
+    let unit = string[number_len..].trim_start();
+
+    if !number.bytes().any(|b| b.is_ascii_digit()) || number.matches('.').count() > 1 {
+        let msg = format!(
+            "invalid byte size `{}`; expected a value like `64MiB`",
+            string
+        );
+        return Err(Error::new(msg));
+    }
+
+    let multiplier = match unit_multiplier(unit) {
+        Some(multiplier) => u128::from(multiplier),
+        None => {
+            let msg = format!(
+                "unknown unit `{}` in byte size `{}`; expected one of B, KB, KiB, MB, MiB, GB, GiB, TB, TiB, PB, PiB, EB, EiB",
+                unit, string,
+            );
+            return Err(Error::new(msg));
+        }
+    };
+
+    let (whole, fraction) = match number.find('.') {
+        Some(dot) => (&number[..dot], &number[dot + 1..]),
+        None => (number, ""),
+    };
+    let out_of_range = || {
+        Error::new(format!(
+            "byte size `{}` is out of range; the maximum is {} bytes",
+            string,
+            u64::MAX,
+        ))
+    };
+
+    let mut bytes = 0u128;
+    for digit in whole.bytes() {
+        bytes = bytes
+            .checked_mul(10)
+            .and_then(|bytes| bytes.checked_add(u128::from(digit - b'0')))
+            .filter(|&bytes| bytes <= u128::from(u64::MAX))
+            .ok_or_else(out_of_range)?;
+    }
+    bytes = bytes.checked_mul(multiplier).ok_or_else(out_of_range)?;
+
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > 18 {
+        let msg = format!("byte size `{}` is not a whole number of bytes", string);
+        return Err(Error::new(msg));
+    }
+    let mut scale = 1u128;
+    let mut fraction_value = 0u128;
+    for digit in fraction.bytes() {
+        scale *= 10;
+        fraction_value = fraction_value * 10 + u128::from(digit - b'0');
+    }
+    let fraction_bytes = fraction_value * multiplier;
+    if fraction_bytes % scale != 0 {
+        let msg = format!("byte size `{}` is not a whole number of bytes", string);
+        return Err(Error::new(msg));
+    }
+    bytes += fraction_bytes / scale;
+
+    if bytes > u128::from(u64::MAX) {
+        return Err(out_of_range());
+    }
+    Ok(ByteSize(bytes as u64))
+}
+
+fn unit_multiplier(unit: &str) -> Option<u64> {
+    if unit.is_empty() {
+        return Some(1);
+    }
+    for &(name, multiplier) in UNITS {
+        if unit.eq_ignore_ascii_case(name) {
+            return Some(multiplier);
+        }
+    }
+    // Bare K, M, G, ... are binary units, as in GNU coreutils.
+    let mut chars = unit.chars();
+    let first = chars.next()?.to_ascii_uppercase();
+    if chars.next().is_some() {
+        return None;
+    }
+    UNITS
+        .iter()
+        .find(|(name, _)| name.len() == 3 && name.starts_with(first))
+        .map(|&(_, multiplier)| multiplier)
+}
+
+impl Display for ByteSize {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let bytes = self.0;
+        if bytes == 0 {
+            return formatter.write_str("0B");
+        }
+
+        // Use the largest unit which represents the value exactly with at most
+        // 3 decimal places. UNITS is sorted from largest to smallest.
+        let mut best = ("B", 1, 0);
+        for &(name, multiplier) in UNITS {
+            if bytes < multiplier {
+                continue;
+            }
+            let decimals = (0..=3).find(|&decimals| {
+                let scaled = u128::from(bytes) * 10u128.pow(decimals);
+                scaled % u128::from(multiplier) == 0
+            });
+            if let Some(decimals) = decimals {
+                best = (name, multiplier, decimals);
+                break;
+            }
+        }
+
+        let (name, multiplier, decimals) = best;
+        let whole = bytes / multiplier;
+        let fraction = bytes % multiplier;
+        write!(formatter, "{}", whole)?;
+        if decimals > 0 {
+            let scaled = u128::from(fraction) * 10u128.pow(decimals) / u128::from(multiplier);
+            write!(formatter, ".{:01$}", scaled, decimals as usize)?;
+        }
+        formatter.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_byte_size, ByteSize};
+
+    #[test]
+    fn parse_sizes() {
+        let parse = |string| parse_byte_size(string).unwrap().0;
+        assert_eq!(parse("4096"), 4096);
+        assert_eq!(parse("64KiB"), 64 * 1024);
+        assert_eq!(parse("64kib"), 64 * 1024);
+        assert_eq!(parse("1.5GB"), 1_500_000_000);
+        assert_eq!(parse("512M"), 512 * 1024 * 1024);
+        assert_eq!(parse("2 MB"), 2_000_000);
+        assert_eq!(parse("0.5KiB"), 512);
+        assert_eq!(parse("15EiB"), 15 << 60);
+    }
+
+    #[test]
+    fn reject_bad_sizes() {
+        assert!(parse_byte_size("").is_err());
+        assert!(parse_byte_size("KiB").is_err());
+        assert!(parse_byte_size("12XB").is_err());
+        assert!(parse_byte_size("0.1B").is_err());
+        assert!(parse_byte_size("1.2.3MB").is_err());
+        assert!(parse_byte_size("18446744073709551616").is_err());
+        assert!(parse_byte_size("16EiB").is_err());
+    }
+
+    #[test]
+    fn render_round_trip() {
+        for &(bytes, expected) in &[
+            (0, "0B"),
+            (100, "100B"),
+            (1000, "1KB"),
+            (1536, "1.5KiB"),
+            (64 << 20, "64MiB"),
+            (1_500_000_000, "1.5GB"),
+        ] {
+            let rendered = ByteSize(bytes).to_string();
+            assert_eq!(rendered, expected);
+            assert_eq!(parse_byte_size(&rendered).unwrap().0, bytes);
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use crate::custom::{Arg, Error, Result, Value};
+use std::ffi::OsStr;
+use std::fmt::{self, Debug};
+use std::ops::Deref;
+use std::slice;
+
+/// A list of values separated by `SEP`, which is a comma by default.
+///
+/// Each element is parsed by the `Value` impl of `T`. An empty argument is an
+/// empty list. In help text the flag's placeholder is rendered with the
+/// separator, as in `--language <LANG>[,<LANG>...]`.
+///
+/// # Examples
+///
+/// ```
+/// use gflags::types::List;
+///
+/// gflags::define! {
+///     /// Comma-separated list of languages to offer in the 'lang' menu.
+///     -l, --language <LANG>: List<&str> = List::new(&["english", "french", "german"])
+/// }
+///
+/// gflags::define! {
+///     /// Directories to search, separated by colons.
+///     --path <DIR>: List<&str, ':'> = List::new(&[])
+/// }
+///
+/// fn main() {
+///     gflags::parse();
+///
+///     for language in LANGUAGE.flag.iter() {
+///         println!("offering {}", language);
+///     }
+/// }
+/// ```
+pub struct List<T: 'static, const SEP: char = ','> {
+    items: &'static [T],
+}
+
+impl<T, const SEP: char> List<T, SEP> {
+    pub const fn new(items: &'static [T]) -> Self {
+        List { items }
+    }
+
+    pub const fn as_slice(&self) -> &'static [T] {
+        self.items
+    }
+}
+
+impl<T: Value, const SEP: char> Value for List<T, SEP> {
+    fn parse(arg: Arg) -> Result<Self> {
+        let name = arg.name();
+        let string = arg.get_str();
+        if string.is_empty() {
+            return Ok(List::new(&[]));
+        }
+
+        let mut items = Vec::new();
+        for (i, element) in string.split(SEP).enumerate() {
+            match T::parse(Arg::new(name, OsStr::new(element))) {
+                Ok(item) => items.push(item),
+                Err(err) => {
+                    let msg = format!("invalid element {}: {}", i + 1, err);
+                    return Err(Error::new(msg));
+                }
+            }
+        }
+        Ok(List::new(Box::leak(items.into_boxed_slice())))
+    }
+
+    fn render(&self) -> Option<String> {
+        let mut string = String::new();
+        for (i, item) in self.items.iter().enumerate() {
+            if i > 0 {
+                string.push(SEP);
+            }
+            string.push_str(&item.render()?);
+        }
+        Some(string)
+    }
+
+    const SEPARATOR: Option<char> = Some(SEP);
+}
+
+impl<T, const SEP: char> Deref for List<T, SEP> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.items
+    }
+}
+
+impl<T, const SEP: char> IntoIterator for &List<T, SEP> {
+    type Item = &'static T;
+    type IntoIter = slice::Iter<'static, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+impl<T, const SEP: char> Copy for List<T, SEP> {}
+
+impl<T, const SEP: char> Clone for List<T, SEP> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Debug, const SEP: char> Debug for List<T, SEP> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_list().entries(self.items).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::List;
+    use crate::custom::{Arg, Value};
+    use crate::name::Name;
+    use std::ffi::OsStr;
+
+    fn arg(string: &'static str) -> Arg {
+        Arg::new(Name::long("test"), OsStr::new(string))
+    }
+
+    #[test]
+    fn parse_list() {
+        let list = List::<u32>::parse(arg("1,0x10,3")).unwrap();
+        assert_eq!(*list, [1, 16, 3]);
+        assert_eq!(list.render().unwrap(), "1,16,3");
+
+        let list = List::<&str, ':'>::parse(arg("/bin:/usr/bin")).unwrap();
+        assert_eq!(*list, ["/bin", "/usr/bin"]);
+
+        assert!(List::<&str>::parse(arg("")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reject_bad_element() {
+        let err = List::<u8>::parse(arg("1,300,3")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid element 2: `300` is out of range for u8, which accepts 0 to 255",
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use crate::custom::{Arg, Error, Result, Value};
+use std::borrow::Borrow;
+use std::ffi::OsStr;
+use std::fmt::{self, Debug};
+use std::slice;
+
+/// A comma-separated list of `key=value` entries, like
+/// `--labels=env=prod,team=infra`.
+///
+/// Keys and values are parsed by the `Value` impls of `K` and `V`. Entries
+/// keep the order in which they were given on the command line, and repeating
+/// a key is an error.
+///
+/// # Examples
+///
+/// ```
+/// use gflags::types::Map;
+///
+/// gflags::define! {
+///     /// Labels to attach to exported metrics.
+///     --labels <"KEY=VALUE">: Map<&str, &str> = Map::new(&[("env", "dev")])
+/// }
+///
+/// fn main() {
+///     gflags::parse();
+///
+///     if let Some(env) = LABELS.flag.get("env") {
+///         println!("environment: {}", env);
+///     }
+/// }
+/// ```
+pub struct Map<K: 'static, V: 'static> {
+    entries: &'static [(K, V)],
+}
+
+impl<K, V> Map<K, V> {
+    pub const fn new(entries: &'static [(K, V)]) -> Self {
+        Map { entries }
+    }
+
+    pub const fn entries(&self) -> &'static [(K, V)] {
+        self.entries
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&'static V>
+    where
+        K: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        self.entries
+            .iter()
+            .find(|(k, _v)| k.borrow() == key)
+            .map(|(_k, v)| v)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    pub fn iter(&self) -> slice::Iter<'static, (K, V)> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<K: Value + PartialEq, V: Value> Value for Map<K, V> {
+    fn parse(arg: Arg) -> Result<Self> {
+        let name = arg.name();
+        let string = arg.get_str();
+        if string.is_empty() {
+            return Ok(Map::new(&[]));
+        }
+
+        let mut entries: Vec<(K, V)> = Vec::new();
+        // Errors identify an entry by position rather than echoing it, since
+        // the key or value may be a Secret.
+        for (i, entry) in string.split(',').enumerate() {
+            let eq = match entry.find('=') {
+                Some(eq) => eq,
+                None => {
+                    let msg = format!("expected key=value in entry {}", i + 1);
+                    return Err(Error::new(msg));
+                }
+            };
+            let (key_str, value_str) = (&entry[..eq], &entry[eq + 1..]);
+            let key = K::parse(Arg::new(name, OsStr::new(key_str)))
+                .map_err(|err| Error::new(format!("invalid key in entry {}: {}", i + 1, err)))?;
+            if entries.iter().any(|(k, _v)| *k == key) {
+                let msg = format!("duplicate key in entry {}", i + 1);
+                return Err(Error::new(msg));
+            }
+            let value = V::parse(Arg::new(name, OsStr::new(value_str)))
+                .map_err(|err| Error::new(format!("invalid value in entry {}: {}", i + 1, err)))?;
+            entries.push((key, value));
+        }
+        Ok(Map::new(Box::leak(entries.into_boxed_slice())))
+    }
+
+    fn render(&self) -> Option<String> {
+        let mut string = String::new();
+        for (i, (key, value)) in self.entries.iter().enumerate() {
+            if i > 0 {
+                string.push(',');
+            }
+            string.push_str(&key.render()?);
+            string.push('=');
+            string.push_str(&value.render()?);
+        }
+        Some(string)
+    }
+
+    const SEPARATOR: Option<char> = Some(',');
+}
+
+impl<K, V> IntoIterator for &Map<K, V> {
+    type Item = &'static (K, V);
+    type IntoIter = slice::Iter<'static, (K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+impl<K, V> Copy for Map<K, V> {}
+
+impl<K, V> Clone for Map<K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for Map<K, V> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_map()
+            .entries(self.entries.iter().map(|(k, v)| (k, v)))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Map;
+    use crate::custom::{Arg, Value};
+    use crate::name::Name;
+    use std::ffi::OsStr;
+
+    fn arg(string: &'static str) -> Arg {
+        Arg::new(Name::long("test"), OsStr::new(string))
+    }
+
+    #[test]
+    fn parse_map() {
+        let map = Map::<&str, u32>::parse(arg("a=1,b=2")).unwrap();
+        assert_eq!(map.get("a"), Some(&1));
+        assert_eq!(map.get("b"), Some(&2));
+        assert_eq!(map.get("c"), None);
+        assert_eq!(map.render().unwrap(), "a=1,b=2");
+
+        let map = Map::<&str, &str>::parse(arg("env=prod,query=a=b")).unwrap();
+        assert_eq!(map.get("query"), Some(&"a=b"));
+    }
+
+    #[test]
+    fn reject_bad_entries() {
+        let err = |string| {
+            Map::<&str, u32>::parse(arg(string))
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(err("a=1,b"), "expected key=value in entry 2");
+        assert_eq!(err("a=1,a=2"), "duplicate key in entry 2");
+        assert_eq!(
+            err("a=x"),
+            "invalid value in entry 1: invalid digit `x` in integer `x`",
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use crate::custom::{Arg, Error, Result, Value};
+use std::ffi::OsStr;
+use std::fmt::{self, Debug};
+use std::fs;
+use std::ops::Deref;
+
+const REDACTED: &str = "<redacted>";
+
+/// A sensitive value, such as a password or API token, that gflags never
+/// prints.
+///
+/// The value is parsed by the `Value` impl of `T`, but wherever gflags would
+/// show it — default values in help text, [`introspect`] and [`flagz`] output,
+/// the admin socket — it is rendered as `<redacted>`. Errors from parsing the
+/// value are replaced by a message that does not contain it, and the `Debug`
+/// representation is redacted as well.
+///
+/// Passing secrets directly on the command line exposes them to other users
+/// of the machine through the process list. Instead, an argument of the form
+/// `@/path/to/file` reads the value from the given file, with one trailing
+/// newline removed. A value which itself begins with `@` may be written with
+/// a doubled `@@`.
+///
+/// [`introspect`]: crate::introspect
+/// [`flagz`]: crate::flagz
+///
+/// # Examples
+///
+/// ```
+/// use gflags::types::Secret;
+///
+/// gflags::define! {
+///     /// Password for the database, or @FILE to read it from a file.
+///     --db_password: Option<Secret<&str>>
+/// }
+///
+/// fn main() {
+///     gflags::parse();
+///
+///     if let Some(password) = DB_PASSWORD.flag {
+///         connect(password.expose());
+///     }
+/// }
+/// #
+/// # fn connect(_password: &str) {}
+/// ```
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+pub struct Secret<T> {
+    value: T,
+}
+
+impl<T> Secret<T> {
+    pub const fn new(value: T) -> Self {
+        Secret { value }
+    }
+
+    /// Access the secret value.
+    pub fn expose(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Value> Value for Secret<T> {
+    fn parse(arg: Arg) -> Result<Self> {
+        let name = arg.name();
+        let raw = arg.get_raw();
+        let raw = match raw.to_str() {
+            Some(string) if string.starts_with("@@") => OsStr::new(&string[1..]),
+            Some(string) if string.starts_with('@') => {
+                let path = &string[1..];
+                let contents = match fs::read_to_string(path) {
+                    Ok(contents) => contents,
+                    Err(err) => {
+                        let msg = format!("failed to read secret from `{}`: {}", path, err);
+                        return Err(Error::new(msg));
+                    }
+                };
+                let mut contents: &'static str = Box::leak(contents.into_boxed_str());
+                if contents.ends_with('\n') {
+                    contents = &contents[..contents.len() - 1];
+                    if contents.ends_with('\r') {
+                        contents = &contents[..contents.len() - 1];
+                    }
+                }
+                OsStr::new(contents)
+            }
+            _ => raw,
+        };
+        match T::parse(Arg::new(name, raw)) {
+            Ok(value) => Ok(Secret::new(value)),
+            Err(_) => Err(Error::new(format!("invalid value {}", REDACTED))),
+        }
+    }
+
+    fn render(&self) -> Option<String> {
+        Some(REDACTED.to_owned())
+    }
+}
+
+impl<T> Deref for Secret<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> Debug for Secret<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(REDACTED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Secret;
+    use crate::custom::{Arg, Value};
+    use crate::name::Name;
+    use crate::types::{List, Map};
+    use std::ffi::OsStr;
+    use std::{env, fs, process};
+
+    fn arg(string: &'static str) -> Arg {
+        Arg::new(Name::long("test"), OsStr::new(string))
+    }
+
+    #[test]
+    fn redacted() {
+        let secret = Secret::<&str>::parse(arg("hunter2")).unwrap();
+        assert_eq!(*secret, "hunter2");
+        assert_eq!(secret.render().unwrap(), "<redacted>");
+        assert_eq!(format!("{:?}", secret), "<redacted>");
+
+        let err = Secret::<u32>::parse(arg("hunter2")).unwrap_err();
+        assert_eq!(err.to_string(), "invalid value <redacted>");
+    }
+
+    #[test]
+    fn redacted_in_collections() {
+        let err = List::<Secret<u32>>::parse(arg("1,hunter2")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid element 2: invalid value <redacted>"
+        );
+
+        let err = Map::<&str, Secret<u32>>::parse(arg("db=hunter2")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value in entry 1: invalid value <redacted>",
+        );
+    }
+
+    #[test]
+    fn from_file() {
+        let path = env::temp_dir().join(format!("gflags-secret-{}", process::id()));
+        fs::write(&path, "hunter2\n").unwrap();
+        let arg_string = format!("@{}", path.display());
+        let secret = Secret::<&str>::parse(arg(Box::leak(arg_string.into_boxed_str())));
+        fs::remove_file(&path).unwrap();
+        assert_eq!(*secret.unwrap(), "hunter2");
+
+        let secret = Secret::<&str>::parse(arg("@@literal")).unwrap();
+        assert_eq!(*secret, "@literal");
+
+        let err = Secret::<&str>::parse(arg("@/nonexistent/secret")).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("failed to read secret from `/nonexistent/secret`: "));
+    }
+}
@@ -1,8 +1,11 @@
 use crate::custom::Arg;
 use crate::error::{Error, Result};
 use crate::state::Flag;
+use std::convert::TryFrom;
 use std::ffi::OsStr;
+use std::fmt::{Display, Write};
 use std::path::Path;
+use std::time::Duration;
 
 /// Types that may be the data type of a flag.
 ///
@@ -13,20 +16,58 @@ use std::path::Path;
 pub trait Value: Sized + 'static {
     fn parse(arg: Arg) -> Result<Self>;
 
+    /// Render the value in a form that `parse` would accept back, for example
+    /// to report the current value of a flag through [`introspect`].
+    ///
+    /// [`introspect`]: crate::introspect
+    ///
+    /// Returns `None` by default, in which case no value is shown.
+    fn render(&self) -> Option<String> {
+        None
+    }
+
     // Not public API.
     #[doc(hidden)]
     const IS_BOOL: bool = false;
 
+    // Not public API.
+    #[doc(hidden)]
+    const SEPARATOR: Option<char> = None;
+
+    // Not public API.
+    #[doc(hidden)]
+    const SHOW_DEFAULT: bool = false;
+
     // Not public API.
     #[doc(hidden)]
     fn set_bool(_flag: &Flag<Self>, _value: &'static bool) {
         panic!("not bool");
     }
+
+    // Not public API.
+    #[doc(hidden)]
+    fn from_bool(_value: bool) -> Self {
+        panic!("not bool");
+    }
 }
 
+/// Booleans given explicitly as `--flag=value` accept `true`, `t`, `yes`, `y`,
+/// `1` and `false`, `f`, `no`, `n`, `0`, in any case.
 impl Value for bool {
-    fn parse(_arg: Arg) -> Result<Self> {
-        panic!("bool flag does not accept argument");
+    fn parse(arg: Arg) -> Result<Self> {
+        let string = arg.get_str();
+        match string.to_ascii_lowercase().as_str() {
+            "true" | "t" | "yes" | "y" | "1" => Ok(true),
+            "false" | "f" | "no" | "n" | "0" => Ok(false),
+            _ => {
+                let msg = format!("invalid boolean `{}`; expected true or false", string);
+                Err(Error::new(msg))
+            }
+        }
+    }
+
+    fn render(&self) -> Option<String> {
+        Some(self.to_string())
     }
 
     const IS_BOOL: bool = true;
@@ -34,24 +75,70 @@ impl Value for bool {
     fn set_bool(flag: &Flag<Self>, value: &'static bool) {
         flag.set_bool(value);
     }
+
+    fn from_bool(value: bool) -> Self {
+        value
+    }
+}
+
+/// An optional flag is `None` when absent from the command line, unless given
+/// a different default, and `Some` of the parsed value when present.
+///
+/// `Option<bool>` behaves like a bool flag and may be used as a tri-state
+/// toggle: `None` if not given, `Some(true)` for `--flag` and `Some(false)` for
+/// `--noflag`.
+impl<T: Value> Value for Option<T> {
+    fn parse(arg: Arg) -> Result<Self> {
+        T::parse(arg).map(Some)
+    }
+
+    fn render(&self) -> Option<String> {
+        self.as_ref().and_then(T::render)
+    }
+
+    const IS_BOOL: bool = T::IS_BOOL;
+
+    const SEPARATOR: Option<char> = T::SEPARATOR;
+
+    const SHOW_DEFAULT: bool = T::SHOW_DEFAULT;
+
+    fn set_bool(flag: &Flag<Self>, value: &'static bool) {
+        flag.set(Some(T::from_bool(*value)));
+    }
+
+    fn from_bool(value: bool) -> Self {
+        Some(T::from_bool(value))
+    }
 }
 
 impl Value for &'static str {
     fn parse(arg: Arg) -> Result<Self> {
         Ok(arg.get_str())
     }
+
+    fn render(&self) -> Option<String> {
+        Some((*self).to_owned())
+    }
 }
 
 impl Value for &'static OsStr {
     fn parse(arg: Arg) -> Result<Self> {
         Ok(arg.get_raw())
     }
+
+    fn render(&self) -> Option<String> {
+        self.to_str().map(str::to_owned)
+    }
 }
 
 impl Value for &'static Path {
     fn parse(arg: Arg) -> Result<Self> {
         <&OsStr>::parse(arg).map(Path::new)
     }
+
+    fn render(&self) -> Option<String> {
+        self.to_str().map(str::to_owned)
+    }
 }
 
 macro_rules! impl_value_for_primitive {
@@ -61,9 +148,408 @@ macro_rules! impl_value_for_primitive {
                 fn parse(arg: Arg) -> Result<Self> {
                     arg.get_str().parse().map_err(Error)
                 }
+
+                fn render(&self) -> Option<String> {
+                    Some(self.to_string())
+                }
             }
         )*
     };
 }
 
-impl_value_for_primitive!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize f32 f64);
+impl_value_for_primitive!(f32 f64);
+
+/// Integers are accepted in decimal, or in hexadecimal, octal or binary with
+/// a `0x`, `0o` or `0b` prefix respectively. Underscores may be used to
+/// separate digits, and a leading `+` or `-` sign is allowed. A leading zero
+/// without a prefix does not make the number octal: `0644` is decimal 644.
+macro_rules! impl_value_for_int {
+    ($($int:ident)*) => {
+        $(
+            impl Int for $int {
+                const MIN: Self = $int::MIN;
+                const MAX: Self = $int::MAX;
+                const NAME: &'static str = stringify!($int);
+            }
+
+            impl Value for $int {
+                fn parse(arg: Arg) -> Result<Self> {
+                    parse_int(arg.get_str())
+                }
+
+                fn render(&self) -> Option<String> {
+                    Some(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_value_for_int!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+
+pub(crate) trait Int: TryFrom<u128> + TryFrom<i128> + Display {
+    const MIN: Self;
+    const MAX: Self;
+    const NAME: &'static str;
+}
+
+pub(crate) fn parse_int<T: Int>(string: &str) -> Result<T> {
+    let (negative, unsigned) = if string.starts_with('-') {
+        (true, &string[1..])
+    } else if string.starts_with('+') {
+        (false, &string[1..])
+    } else {
+        (false, string)
+    };
+
+    let lower = unsigned.get(..2).map(str::to_ascii_lowercase);
+    let (radix, radix_name, digits) = match lower.as_deref() {
+        Some("0x") => (16, "hexadecimal ", &unsigned[2..]),
+        Some("0o") => (8, "octal ", &unsigned[2..]),
+        Some("0b") => (2, "binary ", &unsigned[2..]),
+        _ => (10, "", unsigned),
+    };
+
+    let mut magnitude = 0u128;
+    let mut any_digits = false;
+    let mut overflow = false;
+    for ch in digits.chars() {
+        if ch == '_' {
+            continue;
+        }
+        let digit = match ch.to_digit(radix) {
+            Some(digit) => digit,
+            None => {
+                let msg = format!(
+                    "invalid digit `{}` in {}integer `{}`",
+                    ch.escape_default(),
+                    radix_name,
+                    string,
+                );
+                return Err(Error::new(msg));
+            }
+        };
+        any_digits = true;
+        match magnitude
+            .checked_mul(u128::from(radix))
+            .and_then(|magnitude| magnitude.checked_add(u128::from(digit)))
+        {
+            Some(next) => magnitude = next,
+            None => overflow = true,
+        }
+    }
+
+    if !any_digits {
+        let msg = if digits.len() == unsigned.len() {
+            format!("expected integer, found `{}`", string)
+        } else {
+            let prefix = &string[..string.len() - digits.len()];
+            format!("missing digits after `{}` in `{}`", prefix, string)
+        };
+        return Err(Error::new(msg));
+    }
+
+    let value = if overflow {
+        None
+    } else if negative {
+        // The magnitude of i128::MIN is one more than i128::MAX.
+        match i128::try_from(magnitude) {
+            Ok(magnitude) => T::try_from(-magnitude).ok(),
+            Err(_) if magnitude == 1 << 127 => T::try_from(i128::MIN).ok(),
+            Err(_) => None,
+        }
+    } else {
+        T::try_from(magnitude).ok()
+    };
+
+    value.ok_or_else(|| {
+        Error::new(format!(
+            "`{}` is out of range for {}, which accepts {} to {}",
+            string,
+            T::NAME,
+            T::MIN,
+            T::MAX,
+        ))
+    })
+}
+
+/// Durations are written as a sequence of decimal numbers each with a unit
+/// suffix, like `500ms`, `30s`, `1.5s` or `1h30m`. The supported units are
+/// `ns`, `us` (or `µs`), `ms`, `s`, `m`, `h` and `d`.
+impl Value for Duration {
+    fn parse(arg: Arg) -> Result<Self> {
+        parse_duration(arg.get_str())
+    }
+
+    fn render(&self) -> Option<String> {
+        Some(render_duration(*self))
+    }
+
+    const SHOW_DEFAULT: bool = true;
+}
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+fn parse_duration(string: &str) -> Result<Duration> {
+    if string == "0" {
+        return Ok(Duration::from_secs(0));
+    }
+    if string.is_empty() {
+        return Err(Error::new("empty duration; expected a value like `30s`"));
+    }
+
+    let mut nanos = 0u128;
+    let mut rest = string;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|ch: char| !ch.is_ascii_digit() && ch != '.')
+            .unwrap_or(rest.len());
+        let number = &rest[..number_len];
+        rest = &rest[number_len..];
+        let unit_len = rest
+            .find(|ch: char| ch.is_ascii_digit() || ch == '.')
+            .unwrap_or(rest.len());
+        let unit = &rest[..unit_len];
+        rest = &rest[unit_len..];
+
+        if !number.bytes().any(|b| b.is_ascii_digit()) || number.matches('.').count() > 1 {
+            let msg = format!("invalid number in duration `{}`", string);
+            return Err(Error::new(msg));
+        }
+
+        let unit_nanos = match unit {
+            "ns" => 1,
+            "us" | "µs" => 1_000,
+            "ms" => 1_000_000,
+            "s" => NANOS_PER_SEC,
+            "m" => 60 * NANOS_PER_SEC,
+            "h" => 60 * 60 * NANOS_PER_SEC,
+            "d" => 24 * 60 * 60 * NANOS_PER_SEC,
+            "" if number.len() == string.len() => {
+                let msg = format!(
+                    "missing unit in duration `{0}`; write it as `{0}s`, `{0}ms`, etc.",
+                    string,
+                );
+                return Err(Error::new(msg));
+            }
+            "" => {
+                let msg = format!("missing unit after `{}` in duration `{}`", number, string);
+                return Err(Error::new(msg));
+            }
+            _ => {
+                let msg = format!(
+                    "unknown unit `{}` in duration `{}`; expected one of ns, us, ms, s, m, h, d",
+                    unit, string,
+                );
+                return Err(Error::new(msg));
+            }
+        };
+
+        let (whole, fraction) = match number.find('.') {
+            Some(dot) => (&number[..dot], &number[dot + 1..]),
+            None => (number, ""),
+        };
+        let value = parse_digits(whole)
+            .and_then(|whole| whole.checked_mul(unit_nanos))
+            .ok_or_else(|| duration_out_of_range(string))?;
+        let mut scale = 1u128;
+        let mut fraction_value = 0u128;
+        for digit in fraction.bytes().take(18) {
+            scale *= 10;
+            fraction_value = fraction_value * 10 + u128::from(digit - b'0');
+        }
+        nanos = value
+            .checked_add(fraction_value * unit_nanos / scale)
+            .and_then(|value| nanos.checked_add(value))
+            .ok_or_else(|| duration_out_of_range(string))?;
+    }
+
+    let secs = nanos / NANOS_PER_SEC;
+    if secs > u128::from(u64::MAX) {
+        return Err(duration_out_of_range(string));
+    }
+    Ok(Duration::new(secs as u64, (nanos % NANOS_PER_SEC) as u32))
+}
+
+fn parse_digits(digits: &str) -> Option<u128> {
+    let mut value = 0u128;
+    for digit in digits.bytes() {
+        value = value
+            .checked_mul(10)?
+            .checked_add(u128::from(digit - b'0'))?;
+    }
+    Some(value)
+}
+
+fn duration_out_of_range(string: &str) -> Error {
+    Error::new(format!("duration `{}` is out of range", string))
+}
+
+fn render_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let nanos = duration.subsec_nanos();
+    let mut string = String::new();
+
+    if secs == 0 {
+        if nanos == 0 {
+            string.push_str("0s");
+        } else if nanos < 1_000 {
+            let _ = write!(string, "{}ns", nanos);
+        } else if nanos < 1_000_000 {
+            push_decimal(&mut string, u128::from(nanos), 1_000);
+            string.push_str("us");
+        } else {
+            push_decimal(&mut string, u128::from(nanos), 1_000_000);
+            string.push_str("ms");
+        }
+        return string;
+    }
+
+    let hours = secs / 3600;
+    let minutes = secs / 60 % 60;
+    let seconds = secs % 60;
+    if hours > 0 {
+        let _ = write!(string, "{}h", hours);
+    }
+    if minutes > 0 {
+        let _ = write!(string, "{}m", minutes);
+    }
+    if seconds > 0 || nanos > 0 {
+        let value = u128::from(seconds) * NANOS_PER_SEC + u128::from(nanos);
+        push_decimal(&mut string, value, NANOS_PER_SEC);
+        string.push('s');
+    }
+    string
+}
+
+fn push_decimal(string: &mut String, value: u128, unit: u128) {
+    let _ = write!(string, "{}", value / unit);
+    let mut fraction = value % unit;
+    if fraction != 0 {
+        string.push('.');
+        let mut scale = unit / 10;
+        while fraction != 0 {
+            let digit = fraction / scale;
+            string.push(char::from(b'0' + digit as u8));
+            fraction -= digit * scale;
+            scale /= 10;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_duration, parse_int, render_duration, Value};
+    use crate::arg::Arg;
+    use crate::name::Name;
+    use std::ffi::OsStr;
+    use std::time::Duration;
+
+    fn arg(string: &'static str) -> Arg {
+        Arg::new(Name::long("test"), OsStr::new(string))
+    }
+
+    #[test]
+    fn parse_option() {
+        const _: () = assert!(Option::<bool>::IS_BOOL);
+
+        assert_eq!(Option::<u32>::parse(arg("5")).unwrap(), Some(5));
+        assert!(Option::<u32>::parse(arg("five")).is_err());
+        assert_eq!(Option::<u32>::render(&None), None);
+        assert_eq!(Option::<bool>::from_bool(false), Some(false));
+    }
+
+    #[test]
+    fn parse_ints() {
+        assert_eq!(parse_int::<u32>("42").unwrap(), 42);
+        assert_eq!(parse_int::<u32>("+42").unwrap(), 42);
+        assert_eq!(parse_int::<u8>("0xff").unwrap(), 255);
+        assert_eq!(parse_int::<u8>("0XFF").unwrap(), 255);
+        assert_eq!(parse_int::<u16>("0o644").unwrap(), 0o644);
+        assert_eq!(parse_int::<u16>("0644").unwrap(), 644);
+        assert_eq!(parse_int::<u8>("0b1010_1010").unwrap(), 0b1010_1010);
+        assert_eq!(parse_int::<u64>("1_000_000").unwrap(), 1_000_000);
+        assert_eq!(parse_int::<i32>("-0x10").unwrap(), -16);
+        assert_eq!(parse_int::<i8>("-128").unwrap(), -128);
+        assert_eq!(
+            parse_int::<i128>("-170141183460469231731687303715884105728").unwrap(),
+            i128::MIN
+        );
+        assert_eq!(
+            parse_int::<u128>("0xffffffffffffffffffffffffffffffff").unwrap(),
+            u128::MAX
+        );
+    }
+
+    #[test]
+    fn reject_bad_ints() {
+        let err = |string| parse_int::<u8>(string).unwrap_err().to_string();
+        assert_eq!(
+            err("256"),
+            "`256` is out of range for u8, which accepts 0 to 255"
+        );
+        assert_eq!(
+            err("-1"),
+            "`-1` is out of range for u8, which accepts 0 to 255"
+        );
+        assert_eq!(err("0o9"), "invalid digit `9` in octal integer `0o9`");
+        assert_eq!(err("12a"), "invalid digit `a` in integer `12a`");
+        assert_eq!(err("0x"), "missing digits after `0x` in `0x`");
+        assert_eq!(err(""), "expected integer, found ``");
+        assert_eq!(err("_"), "expected integer, found `_`");
+        assert_eq!(
+            parse_int::<i8>("-129").unwrap_err().to_string(),
+            "`-129` is out of range for i8, which accepts -128 to 127",
+        );
+        assert!(parse_int::<u128>("0x1_0000_0000_0000_0000_0000_0000_0000_0000").is_err());
+    }
+
+    #[test]
+    fn parse_durations() {
+        let ms = Duration::from_millis;
+        assert_eq!(parse_duration("0").unwrap(), ms(0));
+        assert_eq!(parse_duration("500ms").unwrap(), ms(500));
+        assert_eq!(parse_duration("30s").unwrap(), ms(30_000));
+        assert_eq!(parse_duration("5m").unwrap(), ms(300_000));
+        assert_eq!(parse_duration("1h30m").unwrap(), ms(5_400_000));
+        assert_eq!(parse_duration("1.5s").unwrap(), ms(1500));
+        assert_eq!(parse_duration("250us").unwrap(), Duration::from_micros(250));
+        assert_eq!(parse_duration("7ns").unwrap(), Duration::from_nanos(7));
+    }
+
+    #[test]
+    fn reject_bad_durations() {
+        let err = parse_duration("30").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "missing unit in duration `30`; write it as `30s`, `30ms`, etc.",
+        );
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("1h30").is_err());
+        assert!(parse_duration("5x").is_err());
+        assert!(parse_duration("1..5s").is_err());
+        assert!(parse_duration("ms").is_err());
+        assert!(parse_duration("99999999999999999999999h").is_err());
+        assert!(parse_duration("94522879700260684295381835.999h").is_err());
+    }
+
+    #[test]
+    fn render_round_trip() {
+        for &(string, expected) in &[
+            ("0s", "0s"),
+            ("7ns", "7ns"),
+            ("1500ns", "1.5us"),
+            ("500ms", "500ms"),
+            ("1.5s", "1.5s"),
+            ("90s", "1m30s"),
+            ("1h30m", "1h30m"),
+            ("2h0.25s", "2h0.25s"),
+            ("1d", "24h"),
+        ] {
+            let duration = parse_duration(string).unwrap();
+            let rendered = render_duration(duration);
+            assert_eq!(rendered, expected);
+            assert_eq!(parse_duration(&rendered).unwrap(), duration);
+        }
+    }
+}
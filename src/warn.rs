@@ -0,0 +1,37 @@
+use crate::atomic::StaticAtomicPtr;
+use std::sync::atomic::Ordering;
+
+static HANDLER: StaticAtomicPtr<fn(&str)> = StaticAtomicPtr::null();
+
+/// Redirect warnings produced while parsing the command line.
+///
+/// Warnings are emitted for example when a flag marked `#[deprecated]` is used
+/// on the command line. By default they are printed to stderr. Call this
+/// before [`gflags::parse()`] to send them somewhere else instead, such as
+/// your application's logger.
+///
+/// [`gflags::parse()`]: crate::parse()
+///
+/// # Example
+///
+/// ```
+/// fn log_warning(message: &str) {
+///     /* ... */
+/// #   let _ = message;
+/// }
+///
+/// fn main() {
+///     gflags::set_warning_handler(log_warning);
+///     gflags::parse();
+/// }
+/// ```
+pub fn set_warning_handler(handler: fn(&str)) {
+    HANDLER.store(Box::leak(Box::new(handler)), Ordering::Release);
+}
+
+pub(crate) fn warn(message: &str) {
+    match HANDLER.try_load(Ordering::Acquire) {
+        Some(handler) => handler(message),
+        None => eprintln!("Warning: {}", message),
+    }
+}
@@ -0,0 +1,71 @@
+#![cfg(all(feature = "admin", unix))]
+
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::UnixStream;
+use std::process;
+
+gflags::define! {
+    --log_level = "info"
+    --max_qps: u32 = 100
+}
+
+#[test]
+fn get_set_list() {
+    let path = env::temp_dir().join(format!("gflags-admin-{}.sock", process::id()));
+    gflags::admin::serve(&path, &["log_level"]).unwrap();
+    let mode = fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    let err = gflags::admin::serve(&path, &[]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AddrInUse);
+
+    {
+        let stream = UnixStream::connect(&path).unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut request = |line: &str| {
+            writeln!(writer, "{line}").unwrap();
+            let mut response = String::new();
+            loop {
+                let len = response.len();
+                reader.read_line(&mut response).unwrap();
+                let last = &response[len..];
+                if last.starts_with("ok") || last.starts_with("error") {
+                    return response;
+                }
+            }
+        };
+
+        assert_eq!(request("get max_qps"), "ok 100\n");
+        assert_eq!(request("set --log_level debug"), "ok\n");
+        assert_eq!(LOG_LEVEL.flag, "debug");
+        assert_eq!(
+            request("set max_qps 5"),
+            "error flag --max_qps is not mutable\n",
+        );
+        assert_eq!(request("list"), "log_level debug\nmax_qps 100\nok\n");
+        assert_eq!(request("get bogus"), "error unknown flag --bogus\n");
+
+        assert_eq!(request("set log_level a\\nb\\\\c"), "ok\n");
+        assert_eq!(LOG_LEVEL.flag, "a\nb\\c");
+        assert_eq!(request("get log_level"), "ok a\\nb\\\\c\n");
+        assert_eq!(request("list"), "log_level a\\nb\\\\c\nmax_qps 100\nok\n");
+        assert_eq!(request("set log_level a\\"), "error trailing backslash\n");
+    }
+
+    let entries = fs::read_dir(path.parent().unwrap()).unwrap();
+    let prefix = format!(".gflags-admin-{}.sock.", process::id());
+    assert!(!entries
+        .flatten()
+        .any(|entry| entry.file_name().to_string_lossy().starts_with(&prefix)));
+
+    let mut stream = UnixStream::connect(&path).unwrap();
+    let _ = stream.write_all("x".repeat(100_000).as_bytes());
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response).unwrap();
+    assert_eq!(response, "error request too long\n");
+
+    let _ = fs::remove_file(&path);
+}
@@ -0,0 +1,178 @@
+#![cfg(feature = "config")]
+
+use assert_cmd::Command;
+use gflags::types::List;
+use gflags::Origin;
+use predicates::prelude::*;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+gflags::define! {
+    --log_level = "info"
+    --max_qps, --qps: u32 = 100
+    --verbose = false
+    --languages: List<&str> = List::new(&[])
+    --server_port: u16 = 80
+    --server_host = "localhost"
+    --timeout_ms: u32 = 1000
+    --region = "us"
+}
+
+fn write(name: &str, contents: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("gflags-config-{}-{}", process::id(), name));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn load_toml_and_json() {
+    let toml = write(
+        "a.toml",
+        r#"
+        log_level = "debug"
+        qps = 250
+        verbose = true
+        languages = ["english", "french"]
+
+        [server]
+        port = 8080
+        "#,
+    );
+    gflags::config::load(&toml).unwrap();
+    assert_eq!(LOG_LEVEL.flag, "debug");
+    assert_eq!(MAX_QPS.flag, 250);
+    assert!(VERBOSE.flag);
+    assert_eq!(*LANGUAGES.flag, ["english", "french"]);
+    assert_eq!(SERVER_PORT.flag, 8080);
+    match SERVER_PORT.origin() {
+        Origin::Config { file, line } => {
+            assert_eq!(file, toml);
+            assert_eq!(line, 8);
+        }
+        origin => panic!("unexpected origin: {:?}", origin),
+    }
+    assert_eq!(SERVER_HOST.origin(), Origin::Default);
+
+    let json = write(
+        "b.json",
+        "{\n  \"server\": {\n    \"host\": \"example.com\"\n  }\n}",
+    );
+    gflags::config::load(&json).unwrap();
+    assert_eq!(SERVER_HOST.flag, "example.com");
+    match SERVER_HOST.origin() {
+        Origin::Config { file, line } => {
+            assert_eq!(file, json);
+            assert_eq!(line, 3);
+        }
+        origin => panic!("unexpected origin: {:?}", origin),
+    }
+
+    let typo = write("c.toml", "max_qsp = 1\nbogus = 2\n");
+    let err = gflags::config::load(&typo).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "{0}: unknown flag `bogus`\n\
+             {0}: unknown flag `max_qsp`; did you mean `max_qps`?",
+            typo.display(),
+        ),
+    );
+
+    let invalid = write(
+        "d.json",
+        r#"{"log_level": "trace", "max_qps": "lots", "verbose": [true]}"#,
+    );
+    let err = gflags::config::load(&invalid).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "{0}: invalid value for `max_qps`: invalid digit `l` in integer `lots`\n\
+             {0}: invalid value for `verbose`: flag does not accept a list of values",
+            invalid.display(),
+        ),
+    );
+    assert_eq!(MAX_QPS.flag, 250);
+    assert_eq!(LOG_LEVEL.flag, "debug");
+
+    for path in &[toml, json, typo, invalid] {
+        fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn environment_over_config() {
+    env::set_var("GFLAGS_TEST_TIMEOUT_MS", "50");
+    gflags::config::load_env("GFLAGS_TEST_").unwrap();
+    assert_eq!(TIMEOUT_MS.flag, 50);
+    assert_eq!(
+        TIMEOUT_MS.origin(),
+        Origin::Environment {
+            var: "GFLAGS_TEST_TIMEOUT_MS",
+        },
+    );
+
+    let toml = write("e.toml", "timeout_ms = 70\nregion = \"eu\"\n");
+    gflags::config::load(&toml).unwrap();
+    fs::remove_file(&toml).unwrap();
+    assert_eq!(TIMEOUT_MS.flag, 50);
+    assert_eq!(REGION.flag, "eu");
+
+    env::set_var("GFLAGS_BAD_REGION", "ap");
+    env::set_var("GFLAGS_BAD_TIMEOUT_MS", "soon");
+    let err = gflags::config::load_env("GFLAGS_BAD_").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid value for environment variable `GFLAGS_BAD_TIMEOUT_MS`: \
+         invalid digit `s` in integer `soon`",
+    );
+    assert_eq!(REGION.flag, "eu");
+}
+
+#[test]
+fn duplicate_alias() {
+    let toml = write("f.toml", "max_qps = 1\nqps = 2\n");
+    let err = gflags::config::load(&toml).unwrap_err();
+    fs::remove_file(&toml).unwrap();
+    assert_eq!(
+        err.to_string(),
+        format!("{}: `max_qps` and `qps` both set --max_qps", toml.display()),
+    );
+}
+
+fn run_example(name: &str, contents: &str, args: &[&str]) -> assert_cmd::assert::Assert {
+    let path = write(name, contents);
+    let mut cmd = Command::cargo_bin("examples/config").unwrap();
+    cmd.arg("--config").arg(&path).args(args);
+    let assert = cmd.assert();
+    fs::remove_file(&path).unwrap();
+    assert
+}
+
+#[test]
+fn command_line_over_config() {
+    run_example("g.toml", "max_qps = 5\n", &["--max_qps", "7"])
+        .success()
+        .stdout(predicate::str::contains(
+            "max_qps = 7 from command line (argv[3])\n",
+        ));
+    run_example("h.toml", "max_qps = 5\n", &[])
+        .success()
+        .stdout(predicate::str::contains("max_qps = 5 from config file"));
+}
+
+#[test]
+fn config_after_parse_is_validated() {
+    run_example("i.toml", "tls = true\n", &[])
+        .failure()
+        .stderr(predicate::str::contains("Flag --tls (defined at"))
+        .stderr(predicate::str::contains(
+            ") requires --tls_cert (defined at",
+        ));
+    run_example("j.toml", "min_workers = 4\n", &["--max_workers", "2"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "--min_workers must not exceed --max_workers\n",
+        ));
+}
@@ -0,0 +1,13 @@
+gflags::define! {
+    #[deprecated(forward_to = "color")]
+    --colour = false
+    --color: &str = "auto"
+}
+
+#[test]
+#[should_panic(
+    expected = "deprecated flag --colour (defined at tests/deprecated.rs:1) is forwarded to --color, but only one of them is a bool flag"
+)]
+fn forward_bool_to_value() {
+    gflags::parse();
+}
@@ -0,0 +1,10 @@
+gflags::define! {
+    --verbose = false
+    --output, --verbose: &str = "-"
+}
+
+#[test]
+#[should_panic(expected = "flag --verbose is defined more than once")]
+fn duplicate_alias() {
+    gflags::parse();
+}
@@ -0,0 +1,47 @@
+use gflags::flagz::{self, Format};
+use gflags::types::Secret;
+
+gflags::define! {
+    /// Maximum queries per second.
+    --max_qps: u32 = 100
+
+    /// Greeting <b>text</b>.
+    --greeting = "a \"quoted\" <value>"
+
+    --output: Option<&std::path::Path>
+
+    --api_key: Secret<&str> = Secret::new("default-key")
+}
+
+#[test]
+fn render_all_formats() {
+    gflags::introspect::find("max_qps")
+        .unwrap()
+        .set("250")
+        .unwrap();
+    gflags::introspect::find("api_key")
+        .unwrap()
+        .set("hunter2")
+        .unwrap();
+
+    assert_eq!(
+        flagz::render(Format::Text),
+        "--api_key=<redacted>  (default: <redacted>; modified; source: runtime)\n\
+         --greeting=a \"quoted\" <value>  (default: a \"quoted\" <value>)\n\
+         --max_qps=250  (default: 100; modified; source: runtime)\n\
+         --output\n",
+    );
+
+    let json = flagz::render(Format::Json);
+    assert!(json.contains(
+        "{\"name\": \"max_qps\", \"value\": \"250\", \"default\": \"100\", \"modified\": true, \"source\": \"runtime\"}"
+    ));
+    assert!(json.contains("{\"name\": \"output\", \"value\": null, \"default\": null, \"modified\": false, \"source\": \"default\"}"));
+    assert!(json.contains("\"value\": \"a \\\"quoted\\\" <value>\""));
+    assert!(!json.contains("hunter2") && !json.contains("default-key"));
+
+    let html = flagz::render(Format::Html);
+    assert!(!html.contains("hunter2"));
+    assert!(html.contains("<td>a &quot;quoted&quot; &lt;value&gt;</td>"));
+    assert!(html.contains("title=\"Greeting &lt;b&gt;text&lt;/b&gt;.\""));
+}
@@ -0,0 +1,52 @@
+use gflags::Origin;
+
+gflags::define! {
+    /// Maximum queries per second.
+    --max_qps, --qps: u32 = 100
+
+    --log_level = "info"
+
+    #[hidden]
+    --debug = false
+}
+
+#[test]
+fn find_by_name_or_alias() {
+    let flag = gflags::introspect::find("max_qps").unwrap();
+    assert_eq!(flag.name(), "max_qps");
+    assert_eq!(flag.aliases(), ["qps"]);
+    assert_eq!(flag.doc(), ["Maximum queries per second."]);
+    assert_eq!(flag.default_value().as_deref(), Some("100"));
+    assert_eq!(gflags::introspect::find("qps").unwrap().name(), "max_qps");
+    assert!(gflags::introspect::find("nope").is_none());
+}
+
+#[test]
+fn list_all_flags() {
+    let names = gflags::introspect::flags()
+        .iter()
+        .map(gflags::introspect::FlagInfo::name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["debug", "log_level", "max_qps"]);
+}
+
+#[test]
+fn set_by_name() {
+    let flag = gflags::introspect::find("log_level").unwrap();
+    assert_eq!(flag.origin(), Origin::Default);
+    flag.set("debug").unwrap();
+    assert_eq!(LOG_LEVEL.flag, "debug");
+    assert!(flag.is_present());
+    assert_eq!(LOG_LEVEL.origin(), Origin::Runtime);
+
+    let flag = gflags::introspect::find("debug").unwrap();
+    assert!(flag.is_hidden());
+    flag.set("true").unwrap();
+    assert!(DEBUG.flag);
+
+    let flag = gflags::introspect::find("max_qps").unwrap();
+    let err = flag.set("lots").unwrap_err();
+    assert_eq!(err.to_string(), "invalid digit `l` in integer `lots`");
+    assert_eq!(MAX_QPS.flag, 100);
+    assert_eq!(flag.origin(), Origin::Default);
+}
@@ -0,0 +1,31 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn test_args_success(args: &[&str], msg: &str) {
+    let mut cmd = Command::cargo_bin("examples/negation").unwrap();
+    cmd.args(args);
+    cmd.assert().success().stdout(predicate::str::contains(msg));
+}
+
+#[test]
+fn flag_named_like_negation() {
+    test_args_success(&["--no-cache"], "cache = true\n");
+    test_args_success(&["--no-cache"], "no_cache = true\n");
+    test_args_success(&["--notify=sms"], "tify = true\n");
+    test_args_success(&["--notify=sms"], "notify = sms\n");
+}
+
+#[test]
+fn negation_of_other_spelling() {
+    test_args_success(&["--nocache"], "cache = false\n");
+    test_args_success(&["--nocache"], "no_cache = false\n");
+    test_args_success(&["--no-tify"], "tify = false\n");
+    test_args_success(&["--no-tify"], "notify = email\n");
+}
+
+#[test]
+fn help_for_taken_negation() {
+    test_args_success(&["--help"], "    -h, --[no-]help\n");
+    test_args_success(&["--help"], "        --cache\n");
+    test_args_success(&["--help"], "        --[no-]tify\n");
+}
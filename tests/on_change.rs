@@ -0,0 +1,23 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+gflags::define! {
+    --max_qps: u32 = 100
+}
+
+#[test]
+fn on_change() {
+    static SEEN: AtomicU32 = AtomicU32::new(0);
+
+    MAX_QPS.on_change(|value| SEEN.store(*value, Ordering::Relaxed));
+    MAX_QPS.on_change(|value| assert_eq!(MAX_QPS.flag, *value));
+
+    // Tolerate whatever arguments the test harness was given.
+    gflags::ParseOptions::new()
+        .pass_through_unknown(true)
+        .parse();
+    assert_eq!(SEEN.load(Ordering::Relaxed), 0);
+
+    let flag = gflags::introspect::find("max_qps").unwrap();
+    flag.set("250").unwrap();
+    assert_eq!(SEEN.load(Ordering::Relaxed), 250);
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::needless_borrows_for_generic_args)]
+
 use assert_cmd::Command;
 use predicates::prelude::*;
 use std::ffi::OsStr;
@@ -59,6 +61,42 @@ fn unrecognized_long_flag_equals() {
     test_args_failure(&["--foo="], "Unrecognized flag: --foo\n");
 }
 
+#[test]
+fn invalid_bool_equals() {
+    test_args_failure(
+        &["--big_menu=maybe"],
+        "Failed to parse `--big_menu`: invalid boolean `maybe`; expected true or false\n",
+    );
+}
+
+#[test]
+fn conflicting_flags() {
+    test_args_failure(&["--help", "--helpfull"], "cannot be used together\n");
+    test_args_failure(
+        &["-h", "--helpfull"],
+        "Flags --help (defined at examples/print.rs:",
+    );
+}
+
+#[test]
+fn required_flags() {
+    test_args_failure(
+        ["--file_encoding", "latin1"],
+        "Flag --file_encoding (defined at examples/print.rs:",
+    );
+    test_args_failure(&["--file_encoding=latin1"], ") requires --file (defined at");
+    test_args_success(
+        ["--file_encoding", "latin1", "--file", "patterns.txt"],
+        "file_encoding = latin1\n",
+    );
+    test_args_success(&["--file", "patterns.txt"], "file_encoding = utf-8\n");
+}
+
+#[test]
+fn on_parsed_hook_fails() {
+    test_args_failure(&["--language="], "--language must not be empty\n");
+}
+
 // Success cases
 
 #[test]
@@ -71,6 +109,11 @@ fn short_language_flag_cuddled() {
     test_args_success(&["-lfrench"], "language = french\n");
 }
 
+#[test]
+fn short_language_flag_equals() {
+    test_args_success(&["-l=french"], "language = =french\n");
+}
+
 #[test]
 fn long_language_flag() {
     test_args_success(&["--language", "french"], "language = french\n");
@@ -81,12 +124,136 @@ fn long_language_flag_equals() {
     test_args_success(&["--language=french"], "language = french\n");
 }
 
+#[test]
+fn long_alias_flag() {
+    test_args_success(&["--lang", "french"], "language = french\n");
+    test_args_success(&["--lang=french"], "language = french\n");
+}
+
+#[test]
+fn help_shows_aliases() {
+    test_args_success(&["--help"], "[aliases: --lang]\n");
+}
+
+#[test]
+fn deprecated_flag_forwards() {
+    let mut cmd = Command::cargo_bin("examples/print").unwrap();
+    cmd.args(&["--locale", "french", "--locale=german"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("language = german\n"))
+        .stderr("Warning: flag --locale is deprecated: use --language instead\n");
+}
+
+#[test]
+fn help_shows_deprecated() {
+    test_args_success(&["--help"], "[deprecated: use --language instead]\n");
+}
+
+#[test]
+fn help_omits_hidden() {
+    let mut cmd = Command::cargo_bin("examples/print").unwrap();
+    cmd.arg("--help");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("debug_args").not());
+}
+
+#[test]
+fn helpfull_shows_hidden() {
+    test_args_success(&["--helpfull"], "--[no-]debug_args\n");
+}
+
+#[test]
+fn hidden_flag_parses() {
+    test_args_success(&["--debug_args", "foo"], "[\n    \"foo\",\n]\n");
+}
+
 #[test]
 fn no_prefix_on_long_args() {
     test_args_success(&["--nobig_menu"], "big_menu = false\n");
 }
 
+#[test]
+fn hyphenated_no_prefix() {
+    test_args_success(&["--no-big_menu"], "big_menu = false\n");
+    test_args_success(&["--help"], "    --[no-]big_menu\n");
+}
+
+#[test]
+fn no_negation_attribute() {
+    test_args_failure(&["--nohelp"], "Unrecognized flag: --nohelp\n");
+    test_args_failure(&["--no-help"], "Unrecognized flag: --no-help\n");
+    test_args_success(&["--help"], "-h, --help\n");
+}
+
+#[test]
+fn bool_equals_false() {
+    test_args_success(&["--big_menu=false"], "big_menu = false\n");
+    test_args_success(&["--big_menu=NO"], "big_menu = false\n");
+    test_args_success(&["--big_menu=0"], "big_menu = false\n");
+}
+
+#[test]
+fn bool_equals_true() {
+    test_args_success(&["--nobig_menu", "--big_menu=yes"], "big_menu = true\n");
+}
+
 #[test]
 fn args_are_passed_through() {
     test_args_success(&["foo"], "args = [\"foo\"]\n");
 }
+
+#[test]
+fn origin() {
+    test_args_success(&[] as &[&str], "language origin = default\n");
+    test_args_success(
+        &["-l", "french"],
+        "language origin = command line (argv[1])\n",
+    );
+    test_args_success(
+        &["foo", "--lang=french"],
+        "language origin = command line (argv[2])\n",
+    );
+    test_args_success(
+        &["--big_menu", "-lfrench"],
+        "language origin = command line (argv[2])\n",
+    );
+}
+
+#[test]
+fn undefok() {
+    test_args_success(&["--undefok=foo", "--foo"], "args = []\n");
+    test_args_success(&["--nofoo", "--undefok=bar,foo"], "args = []\n");
+    test_args_success(&["--foo=1", "--undefok", "foo"], "args = []\n");
+    test_args_success(&["--undefok=foo", "--foo", "bar"], "args = []\n");
+    test_args_success(
+        &["--undefok=foo", "--foo", "-l", "x", "y"],
+        "args = [\"y\"]\n",
+    );
+    test_args_success(
+        &["-x", "1", "-lgerman", "--undefok=x"],
+        "language = german\n",
+    );
+    test_args_success(&["-xvalue", "--undefok=x"], "args = []\n");
+    test_args_success(&["--foo", "bar", "--undefok", "foo"], "args = []\n");
+    test_args_success(&["--legacy_mode"], "args = []\n");
+    test_args_failure(&["--undefok=foo", "--bar"], "Unrecognized flag: --bar\n");
+    test_args_failure(&["-x", "build", "--language"], "Unrecognized flag: -x\n");
+    test_args_failure(
+        &["--bar", "build", "--language"],
+        "Unrecognized flag: --bar\n",
+    );
+}
+
+#[test]
+fn help_shows_duration_default() {
+    test_args_success(&["--help"], "[default: 30s]\n");
+    test_args_success(&["--timeout", "1m30s"], "timeout = 90s\n");
+
+    let mut cmd = Command::cargo_bin("examples/print").unwrap();
+    cmd.arg("--help");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("[default:").count(1));
+}
@@ -0,0 +1,77 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn test_args_success(args: &[&str], msg: &str) {
+    let mut cmd = Command::cargo_bin("examples/strict").unwrap();
+    cmd.args(args);
+    cmd.assert().success().stdout(predicate::str::contains(msg));
+}
+
+fn test_args_failure(args: &[&str], msg: &str) {
+    let mut cmd = Command::cargo_bin("examples/strict").unwrap();
+    cmd.args(args);
+    cmd.assert().failure().stderr(predicate::str::contains(msg));
+}
+
+#[test]
+fn stop_at_first_positional() {
+    test_args_success(
+        &["-v", "input.txt", "-o", "out.txt"],
+        "args = [\"input.txt\", \"-o\", \"out.txt\"]\n",
+    );
+    test_args_success(&["-o", "out.txt", "input.txt"], "output = out.txt\n");
+}
+
+#[test]
+fn no_clusters() {
+    test_args_success(&["-v", "-q"], "verbose = true\n");
+    test_args_failure(&["-vq"], "Unexpected `q` after -v\n");
+    test_args_failure(&["-oout.txt"], "Unexpected `out.txt` after -o\n");
+}
+
+#[test]
+fn short_eq() {
+    test_args_success(&["-o=out.txt"], "output = out.txt\n");
+    test_args_success(&["-o="], "output = \n");
+    test_args_success(&["-v=true"], "verbose = true\n");
+    test_args_success(&["-v", "-v=false"], "verbose = false\n");
+    test_args_failure(&["-v=maybe"], "Failed to parse `-v`");
+}
+
+#[test]
+fn short_count() {
+    test_args_success(&["-v3"], "verbosity = 3\n");
+    test_args_success(&["-v"], "verbosity = 1\n");
+    test_args_success(&["-v2", "-v"], "verbosity = 3\n");
+    test_args_failure(&["-v0"], "Invalid count `0` for -v\n");
+    test_args_failure(&["-v3q"], "Unexpected `q` after -v\n");
+}
+
+#[test]
+fn short_count_overflow() {
+    test_args_success(&["-v4294967295"], "verbosity = 4294967295\n");
+    test_args_success(&["-v", "-v4294967295"], "verbosity = 4294967295\n");
+    test_args_failure(&["-v4294967296"], "Invalid count `4294967296` for -v\n");
+}
+
+#[test]
+fn no_negation() {
+    test_args_failure(&["--noverbose"], "Unrecognized flag: --noverbose\n");
+}
+
+#[test]
+fn help_without_negation() {
+    test_args_success(&["--help"], "    -v, --verbose\n");
+}
+
+#[test]
+fn abbreviations() {
+    test_args_success(&["--verb", "--out=out.txt"], "verbose = true\n");
+    test_args_success(&["--verb", "--out=out.txt"], "output = out.txt\n");
+    test_args_success(&["--verbose"], "verbose = true\n");
+    test_args_failure(
+        &["--ver"],
+        "Ambiguous flag: --ver could be --verbose, --version\n",
+    );
+    test_args_failure(&["--x"], "Unrecognized flag: --x\n");
+}
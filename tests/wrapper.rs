@@ -0,0 +1,47 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn test_forwarded(args: &[&str], msg: &str) {
+    let mut cmd = Command::cargo_bin("examples/wrapper").unwrap();
+    cmd.args(args);
+    cmd.assert().success().stdout(predicate::str::contains(msg));
+}
+
+#[test]
+fn unknown_long_flags() {
+    test_forwarded(
+        &["--color=always", "build", "--dry_run", "--target", "x86"],
+        "forward = [\"--color=always\", \"build\", \"--target\", \"x86\"]\n",
+    );
+    test_forwarded(&["--nocapture"], "forward = [\"--nocapture\"]\n");
+}
+
+#[test]
+fn unknown_short_flags() {
+    test_forwarded(&["-vofile", "-x"], "forward = [\"-ofile\", \"-x\"]\n");
+    test_forwarded(&["-vofile"], "verbose = true\n");
+}
+
+#[test]
+fn double_dash() {
+    test_forwarded(
+        &["--color=always", "--", "-v", "--dry_run"],
+        "forward = [\"--color=always\", \"--\", \"-v\", \"--dry_run\"]\n",
+    );
+    test_forwarded(&["--", "-v"], "verbose = false\n");
+}
+
+#[test]
+fn undefok_dropped() {
+    test_forwarded(
+        &[
+            "--undefok=color,x",
+            "--color",
+            "always",
+            "-x",
+            "build",
+            "--nocapture",
+        ],
+        "forward = [\"--nocapture\"]\n",
+    );
+}